@@ -0,0 +1,98 @@
+use std::path::{Path, PathBuf};
+
+use anyhow::{Result, anyhow};
+use reqwest::{StatusCode, header};
+use sha2::{Digest, Sha256};
+
+use crate::transport::HttpConfig;
+
+const CACHE_DIR: &str = ".evals/cache/cases";
+
+/// Whether `source` is an HTTP(S) URL rather than a local path.
+pub fn is_remote(source: &str) -> bool {
+    source.starts_with("http://") || source.starts_with("https://")
+}
+
+/// Directory that paths referenced by a cases file are relative to: the
+/// file's own directory, or the working directory for remote files.
+pub fn base_dir(source: &str) -> PathBuf {
+    if is_remote(source) {
+        return PathBuf::from(".");
+    }
+    Path::new(source)
+        .parent()
+        .filter(|parent| !parent.as_os_str().is_empty())
+        .map_or_else(|| PathBuf::from("."), Path::to_path_buf)
+}
+
+/// Key of a remote cases file in the on-disk cache.
+pub fn cache_key(source: &str) -> String {
+    format!("{:x}", Sha256::digest(source.as_bytes()))
+}
+
+/// Loads the raw cases file from a local path or an HTTP(S) URL.
+///
+/// Remote files are cached on disk together with their `ETag`, so unchanged
+/// suites are revalidated with `If-None-Match` instead of downloaded again.
+pub async fn read_cases_source(
+    source: &str,
+    headers: &[String],
+    http: &HttpConfig,
+) -> Result<String> {
+    if !is_remote(source) {
+        return tokio::fs::read_to_string(source)
+            .await
+            .map_err(|e| anyhow!("Failed to read cases file '{}': {}", source, e));
+    }
+
+    let cache_key = cache_key(source);
+    let body_path = PathBuf::from(CACHE_DIR).join(format!("{cache_key}.json"));
+    let etag_path = PathBuf::from(CACHE_DIR).join(format!("{cache_key}.etag"));
+
+    let mut request = http.client()?.get(source);
+    for raw_header in headers {
+        let (name, value) = raw_header
+            .split_once(':')
+            .ok_or_else(|| anyhow!("Invalid header '{}', expected 'Name: value'", raw_header))?;
+        request = request.header(name.trim(), value.trim());
+    }
+
+    let cached_etag = tokio::fs::read_to_string(&etag_path).await.ok();
+    if let Some(etag) = &cached_etag
+        && tokio::fs::try_exists(&body_path).await?
+    {
+        request = request.header(header::IF_NONE_MATCH, etag.trim());
+    }
+
+    let response = request
+        .send()
+        .await
+        .map_err(|e| anyhow!("Failed to fetch cases from '{}': {}", source, e))?;
+
+    if response.status() == StatusCode::NOT_MODIFIED {
+        return Ok(tokio::fs::read_to_string(&body_path).await?);
+    }
+
+    if !response.status().is_success() {
+        return Err(anyhow!(
+            "Failed to fetch cases from '{}': HTTP {}",
+            source,
+            response.status()
+        ));
+    }
+
+    let etag = response
+        .headers()
+        .get(header::ETAG)
+        .and_then(|value| value.to_str().ok())
+        .map(str::to_string);
+    let body = response.text().await?;
+
+    if let Some(etag) = etag {
+        tokio::fs::create_dir_all(CACHE_DIR).await?;
+        tokio::fs::write(&body_path, &body).await?;
+        tokio::fs::write(&etag_path, etag).await?;
+    }
+
+    Ok(body)
+}
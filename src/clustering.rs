@@ -0,0 +1,30 @@
+pub fn cosine_similarity(a: &[f32], b: &[f32]) -> f32 {
+    let dot: f32 = a.iter().zip(b).map(|(x, y)| x * y).sum();
+    let norm_a = a.iter().map(|x| x * x).sum::<f32>().sqrt();
+    let norm_b = b.iter().map(|x| x * x).sum::<f32>().sqrt();
+
+    if norm_a == 0.0 || norm_b == 0.0 {
+        0.0
+    } else {
+        dot / (norm_a * norm_b)
+    }
+}
+
+/// Greedily groups embeddings whose similarity to a cluster's first member is
+/// at least `threshold`. Clusters and their members keep input order.
+pub fn cluster_by_similarity(embeddings: &[Vec<f32>], threshold: f32) -> Vec<Vec<usize>> {
+    let mut clusters: Vec<Vec<usize>> = Vec::new();
+
+    for (index, embedding) in embeddings.iter().enumerate() {
+        let existing = clusters
+            .iter_mut()
+            .find(|cluster| cosine_similarity(&embeddings[cluster[0]], embedding) >= threshold);
+
+        match existing {
+            Some(cluster) => cluster.push(index),
+            None => clusters.push(vec![index]),
+        }
+    }
+
+    clusters
+}
@@ -0,0 +1,107 @@
+use std::collections::HashMap;
+
+use similar::{ChangeTag, TextDiff};
+
+use crate::{evaluation::EvaluationReport, model_config::SystemPrompt};
+
+/// Differences between a baseline and a candidate report.
+#[derive(Debug)]
+pub struct ReportComparison {
+    pub pass_rate_delta: f64,
+    pub average_score_delta: f64,
+    /// Unified diff of the system prompts, when they differ.
+    pub system_prompt_diff: Option<String>,
+    /// Unified diff of the judge prompts, when they differ.
+    pub judge_prompt_diff: Option<String>,
+    /// Inputs of cases that passed in the baseline and fail in the candidate.
+    pub regressions: Vec<String>,
+    /// Inputs of cases that failed in the baseline and pass in the candidate.
+    pub improvements: Vec<String>,
+}
+
+pub fn compare_reports(
+    baseline: &EvaluationReport,
+    candidate: &EvaluationReport,
+) -> ReportComparison {
+    let baseline_passed: HashMap<&str, bool> = baseline
+        .results
+        .iter()
+        .map(|result| (result.case.input.as_str(), result.passed))
+        .collect();
+
+    let mut regressions = Vec::new();
+    let mut improvements = Vec::new();
+    for result in &candidate.results {
+        match baseline_passed.get(result.case.input.as_str()) {
+            Some(true) if !result.passed => regressions.push(result.case.input.clone()),
+            Some(false) if result.passed => improvements.push(result.case.input.clone()),
+            _ => {}
+        }
+    }
+
+    ReportComparison {
+        pass_rate_delta: candidate.summary.pass_rate_percent - baseline.summary.pass_rate_percent,
+        average_score_delta: candidate.summary.average_score - baseline.summary.average_score,
+        system_prompt_diff: unified_diff(
+            &baseline
+                .configuration
+                .system
+                .as_ref()
+                .map_or_else(String::new, SystemPrompt::text),
+            &candidate
+                .configuration
+                .system
+                .as_ref()
+                .map_or_else(String::new, SystemPrompt::text),
+            "baseline",
+            "candidate",
+        ),
+        judge_prompt_diff: unified_diff(
+            &baseline.judge_configuration.text(),
+            &candidate.judge_configuration.text(),
+            "baseline",
+            "candidate",
+        ),
+        regressions,
+        improvements,
+    }
+}
+
+/// Line-based unified diff of `old` and `new`, or `None` when they are equal.
+pub fn unified_diff(old: &str, new: &str, old_label: &str, new_label: &str) -> Option<String> {
+    if old == new {
+        return None;
+    }
+
+    Some(
+        TextDiff::from_lines(old, new)
+            .unified_diff()
+            .context_radius(3)
+            .header(old_label, new_label)
+            .to_string(),
+    )
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WordChange {
+    Equal,
+    Removed,
+    Added,
+}
+
+/// Word-level diff of `old` and `new`, with runs of the same change merged.
+pub fn word_diff(old: &str, new: &str) -> Vec<(WordChange, String)> {
+    let mut segments: Vec<(WordChange, String)> = Vec::new();
+    for change in TextDiff::from_words(old, new).iter_all_changes() {
+        let kind = match change.tag() {
+            ChangeTag::Equal => WordChange::Equal,
+            ChangeTag::Delete => WordChange::Removed,
+            ChangeTag::Insert => WordChange::Added,
+        };
+        match segments.last_mut() {
+            Some((last, text)) if *last == kind => text.push_str(change.value()),
+            _ => segments.push((kind, change.value().to_string())),
+        }
+    }
+    segments
+}
@@ -1,11 +1,15 @@
 pub mod anthropic;
 pub mod openai;
+pub mod openai_responses;
 
-use anyhow::Result;
+use anyhow::{Result, anyhow};
 use serde::{Deserialize, Serialize};
 use std::{fmt, sync::Arc};
 
-use crate::ModelConfig;
+use crate::{
+    model_config::{ModelConfig, Provider},
+    transport::{CassetteMode, HttpConfig, create_transport},
+};
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct ToolDefinition {
@@ -43,6 +47,120 @@ pub enum GenerationResult {
     },
 }
 
+/// Log probability of a generated token and of its most likely alternatives.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct TokenLogprob {
+    pub token: String,
+    pub logprob: f64,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub top_logprobs: Vec<TopLogprob>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct TopLogprob {
+    pub token: String,
+    pub logprob: f64,
+}
+
+/// Why the model stopped generating, normalised across providers.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum StopReason {
+    EndTurn,
+    MaxTokens,
+    StopSequence,
+    ToolUse,
+    ContentFilter,
+    Other,
+}
+
+impl StopReason {
+    pub fn from_anthropic(stop_reason: &str) -> Self {
+        match stop_reason {
+            "end_turn" => StopReason::EndTurn,
+            "max_tokens" => StopReason::MaxTokens,
+            "stop_sequence" => StopReason::StopSequence,
+            "tool_use" => StopReason::ToolUse,
+            "refusal" => StopReason::ContentFilter,
+            _ => StopReason::Other,
+        }
+    }
+
+    pub fn from_openai(finish_reason: &str) -> Self {
+        match finish_reason {
+            "stop" => StopReason::EndTurn,
+            "length" => StopReason::MaxTokens,
+            "tool_calls" | "function_call" => StopReason::ToolUse,
+            "content_filter" => StopReason::ContentFilter,
+            _ => StopReason::Other,
+        }
+    }
+
+    /// From a Responses API `status` and `incomplete_details.reason`.
+    pub fn from_openai_responses(
+        status: &str,
+        incomplete_reason: Option<&str>,
+        has_tool_use: bool,
+    ) -> Self {
+        match (status, incomplete_reason) {
+            ("completed", _) if has_tool_use => StopReason::ToolUse,
+            ("completed", _) => StopReason::EndTurn,
+            ("incomplete", Some("max_output_tokens")) => StopReason::MaxTokens,
+            ("incomplete", Some("content_filter")) => StopReason::ContentFilter,
+            _ => StopReason::Other,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default)]
+pub struct TokenUsage {
+    pub input_tokens: u64,
+    pub output_tokens: u64,
+}
+
+/// What the provider reported about a generation besides its content.
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct GenerationMetadata {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub stop_reason: Option<StopReason>,
+    /// The model that served the request, as returned by the API.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub model: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub usage: Option<TokenUsage>,
+    /// Duration of the request that produced the response, excluding rate
+    /// limit retries.
+    #[serde(default)]
+    pub latency_seconds: f64,
+}
+
+/// Everything a provider returned for one generation.
+#[derive(Debug, Default)]
+pub struct GenerationResponse {
+    pub results: Vec<GenerationResult>,
+    /// Per-token log probabilities, when requested and supported by the provider.
+    pub logprobs: Option<Vec<TokenLogprob>>,
+    pub metadata: GenerationMetadata,
+}
+
+impl GenerationResponse {
+    pub fn new(results: Vec<GenerationResult>) -> Self {
+        Self {
+            results,
+            ..Default::default()
+        }
+    }
+
+    /// The results rendered as text, one per line.
+    pub fn output_text(&self) -> String {
+        let mut response = String::new();
+        for result in &self.results {
+            response.push_str(&format!("{result}\n"));
+        }
+        response.trim().to_string()
+    }
+}
+
 impl fmt::Display for GenerationResult {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
@@ -58,19 +176,77 @@ impl fmt::Display for GenerationResult {
     }
 }
 
+/// Merges the top-level fields of `extra_params` into `request_body`.
+pub(crate) fn merge_extra_params(
+    request_body: &mut serde_json::Value,
+    extra_params: &serde_json::Value,
+) {
+    if let (Some(body), Some(extra_params)) =
+        (request_body.as_object_mut(), extra_params.as_object())
+    {
+        for (key, value) in extra_params {
+            body.insert(key.clone(), value.clone());
+        }
+    }
+}
+
 #[async_trait::async_trait]
 pub trait ConversationModel: Send + Sync {
     async fn generate(
         &self,
         prompt: &str,
         config: &ConversationConifg,
-    ) -> Result<Vec<GenerationResult>>;
+    ) -> Result<GenerationResponse>;
 }
 
-pub fn create_model(provider: &str) -> Result<Arc<dyn ConversationModel>> {
+pub fn create_model(provider: Provider) -> Result<Arc<dyn ConversationModel>> {
+    create_model_with_http_config(provider, &HttpConfig::default())
+}
+
+/// Creates a model using `http` for proxies, certificates, base URLs and
+/// cassettes. Replaying cassettes does not require an API key.
+pub fn create_model_with_http_config(
+    provider: Provider,
+    http: &HttpConfig,
+) -> Result<Arc<dyn ConversationModel>> {
+    let api_key = api_key(provider, http)?;
+    let transport = create_transport(http)?;
+    let base_url = http.base_url(provider);
+
     match provider {
-        "anthropic" => Ok(Arc::new(anthropic::AnthropicModel::new()?)),
-        "openai" => Ok(Arc::new(openai::OpenAIModel::new()?)),
-        _ => Err(anyhow::anyhow!("Unsupported provider: {}", provider)),
+        Provider::Anthropic => {
+            let mut model = anthropic::AnthropicModel::with_transport(api_key, transport);
+            if let Some(base_url) = base_url {
+                model = model.with_base_url(base_url);
+            }
+            Ok(Arc::new(model))
+        }
+        Provider::OpenAI => {
+            let mut model = openai::OpenAIModel::with_transport(api_key, transport);
+            if let Some(base_url) = base_url {
+                model = model.with_base_url(base_url);
+            }
+            Ok(Arc::new(model))
+        }
+        Provider::OpenAIResponses => {
+            let mut model =
+                openai_responses::OpenAIResponsesModel::with_transport(api_key, transport);
+            if let Some(base_url) = base_url {
+                model = model.with_base_url(base_url);
+            }
+            Ok(Arc::new(model))
+        }
+    }
+}
+
+pub(crate) fn api_key(provider: Provider, http: &HttpConfig) -> Result<String> {
+    let api_key_var = match provider {
+        Provider::Anthropic => "ANTHROPIC_API_KEY",
+        Provider::OpenAI | Provider::OpenAIResponses => "OPENAI_API_KEY",
+    };
+    match (std::env::var(api_key_var), &http.cassettes) {
+        (Ok(api_key), _) => Ok(api_key),
+        (Err(_), Some(CassetteMode::Replay(_))) => Ok(String::new()),
+        (Err(_), _) => Err(anyhow!("{} environment variable not set", api_key_var)),
     }
 }
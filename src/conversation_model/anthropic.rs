@@ -1,17 +1,49 @@
+use std::sync::Arc;
+
 use anyhow::{Result, anyhow};
 use tokio::time::{Duration, sleep};
 
-use super::{ConversationConifg, ConversationModel, GenerationResult};
+use super::{
+    ConversationConifg, ConversationModel, GenerationMetadata, GenerationResponse,
+    GenerationResult, StopReason, TokenUsage, merge_extra_params,
+};
+use crate::{
+    model_config::{SystemBlock, SystemPrompt},
+    transport::{HttpTransport, ReqwestTransport},
+};
+
+const DEFAULT_BASE_URL: &str = "https://api.anthropic.com";
+
+/// Sent as the `anthropic-version` header.
+pub const API_VERSION: &str = "2023-06-01";
 
 pub struct AnthropicModel {
     api_key: String,
+    base_url: String,
+    transport: Arc<dyn HttpTransport>,
 }
 
 impl AnthropicModel {
     pub fn new() -> Result<Self> {
         let api_key = std::env::var("ANTHROPIC_API_KEY")
             .map_err(|_| anyhow!("ANTHROPIC_API_KEY environment variable not set"))?;
-        Ok(Self { api_key })
+        Ok(Self::with_transport(
+            api_key,
+            Arc::new(ReqwestTransport::new()),
+        ))
+    }
+
+    pub fn with_transport(api_key: String, transport: Arc<dyn HttpTransport>) -> Self {
+        Self {
+            api_key,
+            base_url: DEFAULT_BASE_URL.to_string(),
+            transport,
+        }
+    }
+
+    pub fn with_base_url(mut self, base_url: impl Into<String>) -> Self {
+        self.base_url = base_url.into();
+        self
     }
 }
 
@@ -21,9 +53,7 @@ impl ConversationModel for AnthropicModel {
         &self,
         prompt: &str,
         config: &ConversationConifg,
-    ) -> Result<Vec<GenerationResult>> {
-        let client = reqwest::Client::new();
-
+    ) -> Result<GenerationResponse> {
         let mut request_body = serde_json::json!({
             "model": config.model_config.model,
             "max_tokens": config.model_config.max_tokens,
@@ -32,8 +62,32 @@ impl ConversationModel for AnthropicModel {
             ]
         });
 
-        if let Some(system) = &config.model_config.system {
-            request_body["system"] = serde_json::Value::String(system.clone());
+        let session = config
+            .model_config
+            .session
+            .as_ref()
+            .filter(|session| !session.is_empty());
+        match (&config.model_config.system, session) {
+            (Some(SystemPrompt::Text(system)), None) => {
+                request_body["system"] = serde_json::Value::String(system.clone());
+            }
+            (None, None) => {}
+            (system, session) => {
+                let blocks: Vec<serde_json::Value> = system
+                    .iter()
+                    .flat_map(SystemPrompt::blocks)
+                    .chain(session.map(|session| SystemBlock::new(session.render())))
+                    .map(|block| {
+                        let mut json = serde_json::json!({"type": "text"});
+                        for (key, value) in block.extra {
+                            json[key] = value;
+                        }
+                        json["text"] = serde_json::Value::String(block.text);
+                        json
+                    })
+                    .collect();
+                request_body["system"] = serde_json::Value::Array(blocks);
+            }
         }
 
         if let Some(tools) = &config.model_config.tools {
@@ -75,29 +129,35 @@ impl ConversationModel for AnthropicModel {
             );
         }
 
+        merge_extra_params(&mut request_body, &config.model_config.extra_params);
+
         loop {
-            let response = client
-                .post("https://api.anthropic.com/v1/messages")
-                .header("x-api-key", &self.api_key)
-                .header("anthropic-version", "2023-06-01")
-                .header("Content-Type", "application/json")
-                .json(&request_body)
-                .send()
+            let request_start = std::time::Instant::now();
+            let response = self
+                .transport
+                .post_json(
+                    &format!("{}/v1/messages", self.base_url.trim_end_matches('/')),
+                    &[
+                        ("x-api-key", self.api_key.clone()),
+                        ("anthropic-version", API_VERSION.to_string()),
+                    ],
+                    &request_body,
+                )
                 .await?;
 
-            if response.status() == 429 {
-                if let Some(retry_after) = response.headers().get("retry-after") {
-                    if let Ok(retry_seconds) = retry_after.to_str().unwrap_or("60").parse::<u64>() {
-                        sleep(Duration::from_secs(retry_seconds)).await;
-                        continue;
-                    }
+            if response.status == 429 {
+                if let Some(retry_after) = &response.retry_after
+                    && let Ok(retry_seconds) = retry_after.parse::<u64>()
+                {
+                    sleep(Duration::from_secs(retry_seconds)).await;
+                    continue;
                 }
 
                 sleep(Duration::from_secs(60)).await;
                 continue;
             }
 
-            let json: serde_json::Value = response.json().await?;
+            let json = response.body;
 
             let mut results = Vec::new();
 
@@ -116,9 +176,25 @@ impl ConversationModel for AnthropicModel {
 
             if results.is_empty() {
                 return Err(anyhow!("No valid content found in response"));
-            } else {
-                return Ok(results);
             }
+
+            let metadata = GenerationMetadata {
+                stop_reason: json["stop_reason"].as_str().map(StopReason::from_anthropic),
+                model: json["model"].as_str().map(str::to_string),
+                usage: json["usage"]["input_tokens"]
+                    .as_u64()
+                    .zip(json["usage"]["output_tokens"].as_u64())
+                    .map(|(input_tokens, output_tokens)| TokenUsage {
+                        input_tokens,
+                        output_tokens,
+                    }),
+                latency_seconds: request_start.elapsed().as_secs_f64(),
+            };
+
+            return Ok(GenerationResponse {
+                metadata,
+                ..GenerationResponse::new(results)
+            });
         }
     }
 }
@@ -1,17 +1,44 @@
 use anyhow::{Result, anyhow};
-use std::time::Duration;
+use std::{sync::Arc, time::Duration};
 
-use super::{ConversationConifg, ConversationModel, GenerationResult};
+use super::{
+    ConversationConifg, ConversationModel, GenerationMetadata, GenerationResponse,
+    GenerationResult, StopReason, TokenUsage, merge_extra_params,
+};
+use crate::{
+    model_config::SystemPrompt,
+    transport::{HttpTransport, ReqwestTransport},
+};
+
+const DEFAULT_BASE_URL: &str = "https://api.openai.com/v1";
 
 pub struct OpenAIModel {
     api_key: String,
+    base_url: String,
+    transport: Arc<dyn HttpTransport>,
 }
 
 impl OpenAIModel {
     pub fn new() -> Result<Self> {
         let api_key = std::env::var("OPENAI_API_KEY")
             .map_err(|_| anyhow!("OPENAI_API_KEY environment variable not set"))?;
-        Ok(Self { api_key })
+        Ok(Self::with_transport(
+            api_key,
+            Arc::new(ReqwestTransport::new()),
+        ))
+    }
+
+    pub fn with_transport(api_key: String, transport: Arc<dyn HttpTransport>) -> Self {
+        Self {
+            api_key,
+            base_url: DEFAULT_BASE_URL.to_string(),
+            transport,
+        }
+    }
+
+    pub fn with_base_url(mut self, base_url: impl Into<String>) -> Self {
+        self.base_url = base_url.into();
+        self
     }
 }
 
@@ -21,15 +48,35 @@ impl ConversationModel for OpenAIModel {
         &self,
         prompt: &str,
         config: &ConversationConifg,
-    ) -> Result<Vec<GenerationResult>> {
-        let client = reqwest::Client::new();
-
+    ) -> Result<GenerationResponse> {
         let mut messages = Vec::new();
 
-        if let Some(system) = &config.model_config.system {
+        match &config.model_config.system {
+            Some(SystemPrompt::Text(system)) => {
+                messages.push(serde_json::json!({
+                    "role": "system",
+                    "content": system
+                }));
+            }
+            Some(SystemPrompt::Blocks(blocks)) => {
+                let parts: Vec<serde_json::Value> = blocks
+                    .iter()
+                    .map(|block| serde_json::json!({"type": "text", "text": block.text}))
+                    .collect();
+                messages.push(serde_json::json!({
+                    "role": "system",
+                    "content": parts
+                }));
+            }
+            None => {}
+        }
+
+        if let Some(session) = &config.model_config.session
+            && !session.is_empty()
+        {
             messages.push(serde_json::json!({
                 "role": "system",
-                "content": system
+                "content": session.render()
             }));
         }
 
@@ -76,38 +123,40 @@ impl ConversationModel for OpenAIModel {
             );
         }
 
+        if let Some(top_logprobs) = config.model_config.logprobs {
+            request_body["logprobs"] = serde_json::Value::Bool(true);
+            request_body["top_logprobs"] =
+                serde_json::Value::Number(serde_json::Number::from(top_logprobs));
+        }
+
         if let Some(top_p) = config.model_config.top_p {
             request_body["top_p"] = serde_json::Value::Number(
                 serde_json::Number::from_f64(top_p).unwrap_or_else(|| serde_json::Number::from(0)),
             );
         }
 
+        merge_extra_params(&mut request_body, &config.model_config.extra_params);
+
         let mut retry_delay = Duration::from_secs(1);
         const MAX_RETRY_DELAY: Duration = Duration::from_secs(60);
 
         loop {
-            let response = client
-                .post("https://api.openai.com/v1/chat/completions")
-                .header("Authorization", format!("Bearer {}", self.api_key))
-                .header("Content-Type", "application/json")
-                .json(&request_body)
-                .send()
+            let request_start = std::time::Instant::now();
+            let response = self
+                .transport
+                .post_json(
+                    &format!("{}/chat/completions", self.base_url.trim_end_matches('/')),
+                    &[("Authorization", format!("Bearer {}", self.api_key))],
+                    &request_body,
+                )
                 .await?;
 
-            if response.status() == 429 {
-                let wait_time = if let Some(retry_after) = response.headers().get("retry-after") {
-                    if let Ok(retry_after_str) = retry_after.to_str() {
-                        if let Ok(seconds) = retry_after_str.parse::<u64>() {
-                            Duration::from_secs(seconds)
-                        } else {
-                            retry_delay
-                        }
-                    } else {
-                        retry_delay
-                    }
-                } else {
-                    retry_delay
-                };
+            if response.status == 429 {
+                let wait_time = response
+                    .retry_after
+                    .as_deref()
+                    .and_then(|retry_after| retry_after.parse::<u64>().ok())
+                    .map_or(retry_delay, Duration::from_secs);
 
                 tokio::time::sleep(wait_time).await;
 
@@ -115,15 +164,16 @@ impl ConversationModel for OpenAIModel {
                 continue;
             }
 
-            let json: serde_json::Value = response.json().await?;
+            let json = response.body;
 
             let mut results = Vec::new();
 
-            if let Some(message) = json["choices"][0]["message"].as_object() {
-                if let Some(content) = message["content"].as_str() {
-                    if !content.is_empty() {
-                        results.push(GenerationResult::Text(content.to_string()));
-                    }
+            let message = &json["choices"][0]["message"];
+            if message.is_object() {
+                if let Some(content) = message["content"].as_str()
+                    && !content.is_empty()
+                {
+                    results.push(GenerationResult::Text(content.to_string()));
                 }
 
                 if let Some(tool_calls) = message["tool_calls"].as_array() {
@@ -145,7 +195,35 @@ impl ConversationModel for OpenAIModel {
                 results.push(GenerationResult::Text("Failed to get response".to_string()));
             }
 
-            return Ok(results);
+            let logprobs = json["choices"][0]["logprobs"]["content"]
+                .as_array()
+                .map(|tokens| {
+                    tokens
+                        .iter()
+                        .filter_map(|token| serde_json::from_value(token.clone()).ok())
+                        .collect()
+                });
+
+            let metadata = GenerationMetadata {
+                stop_reason: json["choices"][0]["finish_reason"]
+                    .as_str()
+                    .map(StopReason::from_openai),
+                model: json["model"].as_str().map(str::to_string),
+                usage: json["usage"]["prompt_tokens"]
+                    .as_u64()
+                    .zip(json["usage"]["completion_tokens"].as_u64())
+                    .map(|(input_tokens, output_tokens)| TokenUsage {
+                        input_tokens,
+                        output_tokens,
+                    }),
+                latency_seconds: request_start.elapsed().as_secs_f64(),
+            };
+
+            return Ok(GenerationResponse {
+                results,
+                logprobs,
+                metadata,
+            });
         }
     }
 }
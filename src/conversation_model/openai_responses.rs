@@ -0,0 +1,232 @@
+use anyhow::{Result, anyhow};
+use std::{sync::Arc, time::Duration};
+
+use super::{
+    ConversationConifg, ConversationModel, GenerationMetadata, GenerationResponse,
+    GenerationResult, StopReason, TokenUsage, merge_extra_params,
+};
+use crate::transport::{HttpTransport, ReqwestTransport};
+
+const DEFAULT_BASE_URL: &str = "https://api.openai.com/v1";
+
+/// Targets OpenAI's Responses API. Built-in tools declared in the
+/// configuration run on OpenAI's side; only their final answer is returned.
+pub struct OpenAIResponsesModel {
+    api_key: String,
+    base_url: String,
+    transport: Arc<dyn HttpTransport>,
+}
+
+impl OpenAIResponsesModel {
+    pub fn new() -> Result<Self> {
+        let api_key = std::env::var("OPENAI_API_KEY")
+            .map_err(|_| anyhow!("OPENAI_API_KEY environment variable not set"))?;
+        Ok(Self::with_transport(
+            api_key,
+            Arc::new(ReqwestTransport::new()),
+        ))
+    }
+
+    pub fn with_transport(api_key: String, transport: Arc<dyn HttpTransport>) -> Self {
+        Self {
+            api_key,
+            base_url: DEFAULT_BASE_URL.to_string(),
+            transport,
+        }
+    }
+
+    pub fn with_base_url(mut self, base_url: impl Into<String>) -> Self {
+        self.base_url = base_url.into();
+        self
+    }
+}
+
+#[async_trait::async_trait]
+impl ConversationModel for OpenAIResponsesModel {
+    async fn generate(
+        &self,
+        prompt: &str,
+        config: &ConversationConifg,
+    ) -> Result<GenerationResponse> {
+        let mut input = Vec::new();
+
+        if let Some(session) = &config.model_config.session
+            && !session.is_empty()
+        {
+            input.push(serde_json::json!({
+                "role": "developer",
+                "content": session.render()
+            }));
+        }
+
+        input.push(serde_json::json!({
+            "role": "user",
+            "content": prompt
+        }));
+
+        let mut request_body = serde_json::json!({
+            "model": config.model_config.model,
+            "max_output_tokens": config.model_config.max_tokens,
+            "input": input,
+            "store": false
+        });
+
+        if let Some(system) = &config.model_config.system {
+            request_body["instructions"] = serde_json::Value::String(system.text());
+        }
+
+        let mut tool_defs: Vec<serde_json::Value> = config
+            .model_config
+            .tools
+            .iter()
+            .flatten()
+            .map(|tool| {
+                serde_json::json!({
+                    "type": "function",
+                    "name": tool.name,
+                    "description": tool.description,
+                    "parameters": tool.schema
+                })
+            })
+            .collect();
+        tool_defs.extend(config.model_config.builtin_tools.iter().cloned());
+
+        if !tool_defs.is_empty() {
+            request_body["tools"] = serde_json::Value::Array(tool_defs);
+        }
+
+        if let Some(forced_tool) = &config.force_tool {
+            request_body["tool_choice"] = serde_json::json!({
+                "type": "function",
+                "name": forced_tool
+            });
+        }
+
+        if let Some(temperature) = config.model_config.temperature {
+            request_body["temperature"] = serde_json::Value::Number(
+                serde_json::Number::from_f64(temperature)
+                    .unwrap_or_else(|| serde_json::Number::from(0)),
+            );
+        }
+
+        if let Some(top_logprobs) = config.model_config.logprobs {
+            request_body["include"] = serde_json::json!(["message.output_text.logprobs"]);
+            request_body["top_logprobs"] =
+                serde_json::Value::Number(serde_json::Number::from(top_logprobs));
+        }
+
+        if let Some(top_p) = config.model_config.top_p {
+            request_body["top_p"] = serde_json::Value::Number(
+                serde_json::Number::from_f64(top_p).unwrap_or_else(|| serde_json::Number::from(0)),
+            );
+        }
+
+        merge_extra_params(&mut request_body, &config.model_config.extra_params);
+
+        let mut retry_delay = Duration::from_secs(1);
+        const MAX_RETRY_DELAY: Duration = Duration::from_secs(60);
+
+        loop {
+            let request_start = std::time::Instant::now();
+            let response = self
+                .transport
+                .post_json(
+                    &format!("{}/responses", self.base_url.trim_end_matches('/')),
+                    &[("Authorization", format!("Bearer {}", self.api_key))],
+                    &request_body,
+                )
+                .await?;
+
+            if response.status == 429 {
+                let wait_time = response
+                    .retry_after
+                    .as_deref()
+                    .and_then(|retry_after| retry_after.parse::<u64>().ok())
+                    .map_or(retry_delay, Duration::from_secs);
+
+                tokio::time::sleep(wait_time).await;
+
+                retry_delay = std::cmp::min(retry_delay * 2, MAX_RETRY_DELAY);
+                continue;
+            }
+
+            let json = response.body;
+
+            if let Some(message) = json["error"]["message"].as_str() {
+                return Err(anyhow!("OpenAI Responses API error: {}", message));
+            }
+
+            let mut results = Vec::new();
+            let mut logprobs: Option<Vec<_>> = None;
+
+            // Built-in tool calls (`web_search_call`, `file_search_call`, ...) have
+            // already run; their outcome is part of the following message.
+            for item in json["output"].as_array().into_iter().flatten() {
+                match item["type"].as_str() {
+                    Some("message") => {
+                        for content in item["content"].as_array().into_iter().flatten() {
+                            let text = match content["type"].as_str() {
+                                Some("output_text") => content["text"].as_str(),
+                                Some("refusal") => content["refusal"].as_str(),
+                                _ => None,
+                            };
+                            if let Some(text) = text
+                                && !text.is_empty()
+                            {
+                                results.push(GenerationResult::Text(text.to_string()));
+                            }
+
+                            if let Some(tokens) = content["logprobs"].as_array() {
+                                logprobs.get_or_insert_with(Vec::new).extend(
+                                    tokens.iter().filter_map(|token| {
+                                        serde_json::from_value(token.clone()).ok()
+                                    }),
+                                );
+                            }
+                        }
+                    }
+                    Some("function_call") => {
+                        let name = item["name"].as_str().unwrap_or("unknown").to_string();
+                        let arguments: serde_json::Value =
+                            serde_json::from_str(item["arguments"].as_str().unwrap_or("{}"))
+                                .unwrap_or_default();
+                        results.push(GenerationResult::ToolUse { name, arguments });
+                    }
+                    _ => {}
+                }
+            }
+
+            if results.is_empty() {
+                results.push(GenerationResult::Text("Failed to get response".to_string()));
+            }
+
+            let has_tool_use = results
+                .iter()
+                .any(|result| matches!(result, GenerationResult::ToolUse { .. }));
+            let metadata = GenerationMetadata {
+                stop_reason: json["status"].as_str().map(|status| {
+                    StopReason::from_openai_responses(
+                        status,
+                        json["incomplete_details"]["reason"].as_str(),
+                        has_tool_use,
+                    )
+                }),
+                model: json["model"].as_str().map(str::to_string),
+                usage: json["usage"]["input_tokens"]
+                    .as_u64()
+                    .zip(json["usage"]["output_tokens"].as_u64())
+                    .map(|(input_tokens, output_tokens)| TokenUsage {
+                        input_tokens,
+                        output_tokens,
+                    }),
+                latency_seconds: request_start.elapsed().as_secs_f64(),
+            };
+
+            return Ok(GenerationResponse {
+                results,
+                logprobs,
+                metadata,
+            });
+        }
+    }
+}
@@ -0,0 +1,325 @@
+use std::{fmt, path::PathBuf, str::FromStr, sync::Arc};
+
+use anyhow::{Result, anyhow};
+use chrono::{DateTime, Utc};
+use cron::Schedule;
+use serde::{Deserialize, Serialize};
+use tokio::sync::mpsc;
+
+use crate::{
+    cases_source::read_cases_source,
+    conversation_model::create_model_with_http_config,
+    evaluation::EvaluationReport,
+    history::{DEFAULT_HISTORY_DIR, HistoryStore},
+    model_config::{ModelConfig, Provider},
+    runner::EvalRun,
+    suite::Suite,
+    transport::HttpConfig,
+};
+
+#[derive(Debug, Deserialize)]
+pub struct DaemonConfig {
+    #[serde(default = "default_history_dir")]
+    pub history_dir: PathBuf,
+    /// Receives a JSON POST, with a Slack-compatible `text`, when a suite degrades.
+    #[serde(default)]
+    pub webhook_url: Option<String>,
+    pub suites: Vec<ScheduledSuite>,
+}
+
+fn default_history_dir() -> PathBuf {
+    PathBuf::from(DEFAULT_HISTORY_DIR)
+}
+
+fn default_threshold() -> f64 {
+    0.8
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ScheduledSuite {
+    pub name: String,
+    /// Cron expression, with an optional leading seconds field.
+    pub schedule: String,
+    pub cases_file: String,
+    pub configuration: ModelConfig,
+    #[serde(default = "default_threshold")]
+    pub threshold: f64,
+    #[serde(default)]
+    pub tolerances: Tolerances,
+    /// Run `setup`/`teardown` commands and scorers from a remote cases file.
+    #[serde(default)]
+    pub allow_case_commands: bool,
+}
+
+impl ScheduledSuite {
+    pub async fn execute(&self, http: &HttpConfig) -> Result<EvaluationReport> {
+        let cases_content = read_cases_source(&self.cases_file, &[], http).await?;
+        let mut suite_config = Suite::from_json(&cases_content)?;
+        suite_config.check_case_commands(&self.cases_file, self.allow_case_commands)?;
+        suite_config.preprocess(&self.cases_file).await?;
+
+        let mut config = self.configuration.clone();
+        if config.session.is_none() {
+            config.session = suite_config.session;
+        }
+
+        let run = EvalRun::builder(config.clone())
+            .suite(self.name.clone())
+            .cases(suite_config.cases)
+            .categories(suite_config.categories)
+            .breakdown_by(suite_config.breakdown_by)
+            .threshold(self.threshold)
+            .model(create_model_with_http_config(config.provider, http)?)
+            .judge_model(create_model_with_http_config(Provider::Anthropic, http)?)
+            .build()?;
+
+        run.execute().await
+    }
+}
+
+/// How far each metric may degrade from the previous run before notifying.
+/// Unset metrics are not monitored.
+#[derive(Debug, Deserialize, Default, Clone)]
+pub struct Tolerances {
+    /// Drop in pass rate, in percentage points.
+    pub pass_rate_percent: Option<f64>,
+    pub average_score: Option<f64>,
+    /// Increase in mean generation latency.
+    pub mean_latency_seconds: Option<f64>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct Degradation {
+    pub metric: String,
+    pub previous: f64,
+    pub current: f64,
+    pub tolerance: f64,
+}
+
+impl fmt::Display for Degradation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} {:.2} → {:.2} (tolerance {:.2})",
+            self.metric, self.previous, self.current, self.tolerance
+        )
+    }
+}
+
+/// Metrics of `current` that degraded from `previous` beyond the tolerances.
+pub fn degradations(
+    previous: &EvaluationReport,
+    current: &EvaluationReport,
+    tolerances: &Tolerances,
+) -> Vec<Degradation> {
+    let checks = [
+        (
+            "pass rate",
+            tolerances.pass_rate_percent,
+            previous.summary.pass_rate_percent,
+            current.summary.pass_rate_percent,
+            false,
+        ),
+        (
+            "average score",
+            tolerances.average_score,
+            previous.summary.average_score,
+            current.summary.average_score,
+            false,
+        ),
+        (
+            "mean latency",
+            tolerances.mean_latency_seconds,
+            previous.mean_latency_seconds(),
+            current.mean_latency_seconds(),
+            true,
+        ),
+    ];
+
+    checks
+        .into_iter()
+        .filter_map(|(metric, tolerance, previous, current, higher_is_worse)| {
+            let tolerance = tolerance?;
+            let change = if higher_is_worse {
+                current - previous
+            } else {
+                previous - current
+            };
+            (change > tolerance).then(|| Degradation {
+                metric: metric.to_string(),
+                previous,
+                current,
+                tolerance,
+            })
+        })
+        .collect()
+}
+
+/// Parses a cron expression, accepting the five-field form without seconds.
+pub fn parse_schedule(expression: &str) -> Result<Schedule> {
+    let expression = if expression.split_whitespace().count() == 5 {
+        format!("0 {}", expression)
+    } else {
+        expression.to_string()
+    };
+    Schedule::from_str(&expression).map_err(|e| anyhow!("Invalid schedule '{}': {}", expression, e))
+}
+
+#[derive(Debug)]
+pub enum DaemonEvent {
+    Scheduled {
+        suite: String,
+        at: DateTime<Utc>,
+    },
+    Completed {
+        suite: String,
+        report: Box<EvaluationReport>,
+        saved_to: PathBuf,
+        degradations: Vec<Degradation>,
+    },
+    Failed {
+        suite: String,
+        error: anyhow::Error,
+    },
+}
+
+struct DaemonContext {
+    history: HistoryStore,
+    webhook_url: Option<String>,
+    http: HttpConfig,
+    client: reqwest::Client,
+}
+
+impl DaemonContext {
+    async fn run(&self, suite: &ScheduledSuite) -> Result<DaemonEvent> {
+        let previous = self
+            .history
+            .latest(&suite.name, &suite.configuration)
+            .await?;
+        let report = suite.execute(&self.http).await?;
+        let saved_to = self.history.save(&report).await?;
+
+        let degradations = previous
+            .map(|previous| degradations(&previous, &report, &suite.tolerances))
+            .unwrap_or_default();
+        if !degradations.is_empty()
+            && let Some(webhook_url) = &self.webhook_url
+        {
+            self.notify(webhook_url, suite, &report, &degradations)
+                .await
+                .map_err(|e| anyhow!("Failed to notify about '{}': {}", suite.name, e))?;
+        }
+
+        Ok(DaemonEvent::Completed {
+            suite: suite.name.clone(),
+            report: Box::new(report),
+            saved_to,
+            degradations,
+        })
+    }
+
+    async fn notify(
+        &self,
+        webhook_url: &str,
+        suite: &ScheduledSuite,
+        report: &EvaluationReport,
+        degradations: &[Degradation],
+    ) -> Result<()> {
+        let changes: Vec<String> = degradations.iter().map(ToString::to_string).collect();
+        let payload = serde_json::json!({
+            "text": format!(
+                "evals: suite '{}' degraded on {}/{}: {}",
+                suite.name,
+                report.configuration.provider,
+                report.configuration.model,
+                changes.join("; ")
+            ),
+            "suite": suite.name,
+            "provider": report.configuration.provider,
+            "model": report.configuration.model,
+            "generated_at": report.metadata.generated_at,
+            "degradations": degradations,
+        });
+
+        self.client
+            .post(webhook_url)
+            .json(&payload)
+            .send()
+            .await?
+            .error_for_status()?;
+
+        Ok(())
+    }
+}
+
+/// Runs suites on their schedules, storing every report in the run history
+/// and notifying when a run degrades from the previous one.
+pub struct Daemon {
+    suites: Vec<(ScheduledSuite, Schedule)>,
+    context: Arc<DaemonContext>,
+}
+
+impl Daemon {
+    pub fn new(config: DaemonConfig, http: HttpConfig) -> Result<Self> {
+        let suites = config
+            .suites
+            .into_iter()
+            .map(|suite| {
+                let schedule = parse_schedule(&suite.schedule)?;
+                Ok((suite, schedule))
+            })
+            .collect::<Result<Vec<_>>>()?;
+        if suites.is_empty() {
+            return Err(anyhow!("The daemon configuration has no suites"));
+        }
+
+        Ok(Self {
+            suites,
+            context: Arc::new(DaemonContext {
+                history: HistoryStore::new(config.history_dir),
+                webhook_url: config.webhook_url,
+                client: http.client()?,
+                http,
+            }),
+        })
+    }
+
+    /// Starts one task per suite, reporting progress until the receiver is dropped.
+    pub fn spawn(self) -> mpsc::UnboundedReceiver<DaemonEvent> {
+        let (sender, receiver) = mpsc::unbounded_channel();
+
+        for (suite, schedule) in self.suites {
+            let sender = sender.clone();
+            let context = Arc::clone(&self.context);
+
+            tokio::spawn(async move {
+                while let Some(at) = schedule.upcoming(Utc).next() {
+                    let scheduled = DaemonEvent::Scheduled {
+                        suite: suite.name.clone(),
+                        at,
+                    };
+                    if sender.send(scheduled).is_err() {
+                        return;
+                    }
+
+                    let wait = (at - Utc::now()).to_std().unwrap_or_default();
+                    tokio::time::sleep(wait).await;
+
+                    let event =
+                        context
+                            .run(&suite)
+                            .await
+                            .unwrap_or_else(|error| DaemonEvent::Failed {
+                                suite: suite.name.clone(),
+                                error,
+                            });
+                    if sender.send(event).is_err() {
+                        return;
+                    }
+                }
+            });
+        }
+
+        receiver
+    }
+}
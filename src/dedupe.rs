@@ -0,0 +1,74 @@
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    clustering::{cluster_by_similarity, cosine_similarity},
+    embedding_model::EmbeddingModel,
+    evaluation::EvalCase,
+};
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct DedupeReport {
+    pub threshold: f32,
+    pub original_count: usize,
+    pub kept_count: usize,
+    pub removed_count: usize,
+    pub duplicate_groups: Vec<DuplicateGroup>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct DuplicateGroup {
+    pub kept: String,
+    pub removed: Vec<RemovedCase>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RemovedCase {
+    pub input: String,
+    pub similarity: f32,
+}
+
+/// Keeps the first case of every cluster of near-identical inputs.
+pub async fn dedupe_cases(
+    cases: Vec<EvalCase>,
+    embedding_model: &dyn EmbeddingModel,
+    threshold: f32,
+) -> Result<(Vec<EvalCase>, DedupeReport)> {
+    let inputs: Vec<String> = cases.iter().map(|case| case.input.clone()).collect();
+    let embeddings = embedding_model.embed(&inputs).await?;
+    let clusters = cluster_by_similarity(&embeddings, threshold);
+
+    let duplicate_groups: Vec<DuplicateGroup> = clusters
+        .iter()
+        .filter(|cluster| cluster.len() > 1)
+        .map(|cluster| DuplicateGroup {
+            kept: inputs[cluster[0]].clone(),
+            removed: cluster[1..]
+                .iter()
+                .map(|&index| RemovedCase {
+                    input: inputs[index].clone(),
+                    similarity: cosine_similarity(&embeddings[cluster[0]], &embeddings[index]),
+                })
+                .collect(),
+        })
+        .collect();
+
+    let kept_indices: Vec<usize> = clusters.iter().map(|cluster| cluster[0]).collect();
+
+    let original_count = cases.len();
+    let mut cases: Vec<Option<EvalCase>> = cases.into_iter().map(Some).collect();
+    let kept: Vec<EvalCase> = kept_indices
+        .into_iter()
+        .filter_map(|index| cases[index].take())
+        .collect();
+
+    let report = DedupeReport {
+        threshold,
+        original_count,
+        kept_count: kept.len(),
+        removed_count: original_count - kept.len(),
+        duplicate_groups,
+    };
+
+    Ok((kept, report))
+}
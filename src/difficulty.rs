@@ -0,0 +1,112 @@
+use std::{collections::HashMap, fmt};
+
+use serde::{Deserialize, Serialize};
+
+use crate::evaluation::{EvalCaseReport, EvaluationReport};
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Hash)]
+#[serde(rename_all = "lowercase")]
+pub enum DifficultyBucket {
+    Easy,
+    Medium,
+    Hard,
+}
+
+impl DifficultyBucket {
+    fn from_score(difficulty: f64) -> Self {
+        if difficulty < 1.0 / 3.0 {
+            DifficultyBucket::Easy
+        } else if difficulty < 2.0 / 3.0 {
+            DifficultyBucket::Medium
+        } else {
+            DifficultyBucket::Hard
+        }
+    }
+
+    fn from_label(label: &str) -> Option<Self> {
+        match label.to_lowercase().as_str() {
+            "easy" => Some(DifficultyBucket::Easy),
+            "medium" => Some(DifficultyBucket::Medium),
+            "hard" => Some(DifficultyBucket::Hard),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for DifficultyBucket {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DifficultyBucket::Easy => write!(f, "easy"),
+            DifficultyBucket::Medium => write!(f, "medium"),
+            DifficultyBucket::Hard => write!(f, "hard"),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(tag = "source")]
+pub enum Difficulty {
+    /// Estimated as one minus the mean judge score observed in previous reports.
+    #[serde(rename = "history")]
+    Estimated {
+        bucket: DifficultyBucket,
+        score: f64,
+        observations: usize,
+    },
+    /// Taken from the case's `difficulty` metadata label.
+    #[serde(rename = "metadata")]
+    Labeled { bucket: DifficultyBucket },
+}
+
+impl Difficulty {
+    pub fn bucket(&self) -> DifficultyBucket {
+        match self {
+            Difficulty::Estimated { bucket, .. } | Difficulty::Labeled { bucket } => *bucket,
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct DifficultyEstimator {
+    observations: HashMap<String, Vec<f64>>,
+}
+
+impl DifficultyEstimator {
+    pub fn from_reports(reports: &[EvaluationReport]) -> Self {
+        let mut observations: HashMap<String, Vec<f64>> = HashMap::new();
+
+        for report in reports {
+            for result in &report.results {
+                observations
+                    .entry(result.case.input.clone())
+                    .or_default()
+                    .extend(
+                        result
+                            .iterations
+                            .iter()
+                            .map(|iteration| iteration.judge_score),
+                    );
+            }
+        }
+
+        Self { observations }
+    }
+
+    pub fn estimate(&self, case: &EvalCaseReport) -> Option<Difficulty> {
+        if let Some(scores) = self.observations.get(&case.input)
+            && !scores.is_empty()
+        {
+            let score = 1.0 - scores.iter().sum::<f64>() / scores.len() as f64;
+            return Some(Difficulty::Estimated {
+                bucket: DifficultyBucket::from_score(score),
+                score,
+                observations: scores.len(),
+            });
+        }
+
+        case.metadata
+            .get("difficulty")
+            .and_then(|label| DifficultyBucket::from_label(label))
+            .map(|bucket| Difficulty::Labeled { bucket })
+    }
+}
@@ -0,0 +1,93 @@
+use serde::{Deserialize, Serialize};
+
+/// Stops a run once the pass-rate confidence interval is narrower than
+/// `margin` on each side.
+///
+/// The interval is re-checked after every case without correcting for the
+/// repeated looks, so the achieved coverage is somewhat below `confidence`.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy)]
+pub struct EarlyStopping {
+    pub confidence: f64,
+    pub margin: f64,
+    pub min_cases: usize,
+    pub concurrency: usize,
+}
+
+impl EarlyStopping {
+    pub fn new(margin: f64) -> Self {
+        Self {
+            confidence: 0.95,
+            margin,
+            min_cases: 30,
+            concurrency: 8,
+        }
+    }
+
+    pub fn is_confident(&self, passed: usize, total: usize) -> bool {
+        if total < self.min_cases.max(1) {
+            return false;
+        }
+        let (lower, upper) = wilson_interval(passed, total, self.confidence);
+        (upper - lower) / 2.0 <= self.margin
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct EarlyStoppingReport {
+    pub confidence: f64,
+    pub margin: f64,
+    pub evaluated_cases: usize,
+    pub total_cases: usize,
+    pub stopped_early: bool,
+    pub pass_rate_lower_percent: f64,
+    pub pass_rate_upper_percent: f64,
+}
+
+impl EarlyStoppingReport {
+    pub fn new(
+        early_stopping: &EarlyStopping,
+        passed: usize,
+        evaluated: usize,
+        total: usize,
+    ) -> Self {
+        let (lower, upper) = wilson_interval(passed, evaluated, early_stopping.confidence);
+        Self {
+            confidence: early_stopping.confidence,
+            margin: early_stopping.margin,
+            evaluated_cases: evaluated,
+            total_cases: total,
+            stopped_early: evaluated < total,
+            pass_rate_lower_percent: lower * 100.0,
+            pass_rate_upper_percent: upper * 100.0,
+        }
+    }
+}
+
+/// Wilson score interval for a binomial proportion.
+pub fn wilson_interval(passed: usize, total: usize, confidence: f64) -> (f64, f64) {
+    if total == 0 {
+        return (0.0, 1.0);
+    }
+
+    let n = total as f64;
+    let p = passed as f64 / n;
+    let z = z_score(confidence);
+    let z2 = z * z;
+
+    let center = (p + z2 / (2.0 * n)) / (1.0 + z2 / n);
+    let half_width = z / (1.0 + z2 / n) * (p * (1.0 - p) / n + z2 / (4.0 * n * n)).sqrt();
+
+    (
+        (center - half_width).max(0.0),
+        (center + half_width).min(1.0),
+    )
+}
+
+/// Two-sided standard normal critical value, using the Abramowitz and Stegun
+/// 26.2.23 approximation (error below 4.5e-4).
+fn z_score(confidence: f64) -> f64 {
+    let tail = ((1.0 - confidence) / 2.0).clamp(1e-12, 0.5);
+    let t = (-2.0 * tail.ln()).sqrt();
+    t - (2.515517 + 0.802853 * t + 0.010328 * t * t)
+        / (1.0 + 1.432788 * t + 0.189269 * t * t + 0.001308 * t * t * t)
+}
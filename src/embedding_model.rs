@@ -0,0 +1,46 @@
+pub mod openai;
+
+use std::sync::Arc;
+
+use anyhow::Result;
+
+use crate::{
+    conversation_model::api_key,
+    model_config::Provider,
+    transport::{HttpConfig, create_transport},
+};
+
+#[async_trait::async_trait]
+pub trait EmbeddingModel: Send + Sync {
+    async fn embed(&self, inputs: &[String]) -> Result<Vec<Vec<f32>>>;
+}
+
+pub fn create_embedding_model(provider: &str, model: &str) -> Result<Arc<dyn EmbeddingModel>> {
+    create_embedding_model_with_http_config(provider, model, &HttpConfig::default())
+}
+
+/// Creates an embedding model using `http` for proxies, certificates, base
+/// URLs and cassettes.
+pub fn create_embedding_model_with_http_config(
+    provider: &str,
+    model: &str,
+    http: &HttpConfig,
+) -> Result<Arc<dyn EmbeddingModel>> {
+    match provider {
+        "openai" => {
+            let mut embedding_model = openai::OpenAIEmbeddingModel::with_transport(
+                api_key(Provider::OpenAI, http)?,
+                model,
+                create_transport(http)?,
+            );
+            if let Some(base_url) = http.base_url(Provider::OpenAI) {
+                embedding_model = embedding_model.with_base_url(base_url);
+            }
+            Ok(Arc::new(embedding_model))
+        }
+        _ => Err(anyhow::anyhow!(
+            "Unsupported embedding provider: {}",
+            provider
+        )),
+    }
+}
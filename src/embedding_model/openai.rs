@@ -0,0 +1,87 @@
+use std::sync::Arc;
+
+use anyhow::{Result, anyhow};
+
+use super::EmbeddingModel;
+use crate::transport::{HttpTransport, ReqwestTransport};
+
+const BATCH_SIZE: usize = 256;
+
+pub struct OpenAIEmbeddingModel {
+    api_key: String,
+    model: String,
+    base_url: String,
+    transport: Arc<dyn HttpTransport>,
+}
+
+impl OpenAIEmbeddingModel {
+    pub fn new(model: &str) -> Result<Self> {
+        let api_key = std::env::var("OPENAI_API_KEY")
+            .map_err(|_| anyhow!("OPENAI_API_KEY environment variable not set"))?;
+        Ok(Self::with_transport(
+            api_key,
+            model,
+            Arc::new(ReqwestTransport::new()),
+        ))
+    }
+
+    pub fn with_transport(api_key: String, model: &str, transport: Arc<dyn HttpTransport>) -> Self {
+        Self {
+            api_key,
+            model: model.to_string(),
+            base_url: "https://api.openai.com/v1".to_string(),
+            transport,
+        }
+    }
+
+    pub fn with_base_url(mut self, base_url: impl Into<String>) -> Self {
+        self.base_url = base_url.into();
+        self
+    }
+}
+
+#[async_trait::async_trait]
+impl EmbeddingModel for OpenAIEmbeddingModel {
+    async fn embed(&self, inputs: &[String]) -> Result<Vec<Vec<f32>>> {
+        let mut embeddings = Vec::with_capacity(inputs.len());
+
+        for batch in inputs.chunks(BATCH_SIZE) {
+            let response = self
+                .transport
+                .post_json(
+                    &format!("{}/embeddings", self.base_url.trim_end_matches('/')),
+                    &[("Authorization", format!("Bearer {}", self.api_key))],
+                    &serde_json::json!({
+                        "model": self.model,
+                        "input": batch,
+                    }),
+                )
+                .await?;
+
+            if !(200..300).contains(&response.status) {
+                return Err(anyhow!(
+                    "Embedding request failed ({}): {}",
+                    response.status,
+                    response.body
+                ));
+            }
+
+            let json = response.body;
+            let data = json["data"]
+                .as_array()
+                .ok_or_else(|| anyhow!("No embeddings found in response"))?;
+
+            for item in data {
+                let embedding = item["embedding"]
+                    .as_array()
+                    .ok_or_else(|| anyhow!("Malformed embedding in response"))?
+                    .iter()
+                    .map(|value| value.as_f64().unwrap_or(0.0) as f32)
+                    .collect();
+                embeddings.push(embedding);
+            }
+        }
+
+        Ok(embeddings)
+    }
+}
@@ -1,15 +1,36 @@
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 
+use anyhow::{Result, anyhow};
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
 
-use crate::{judge::JudgePrompt, model_config::ModelConfig};
+use crate::{
+    conversation_model::{GenerationMetadata, TokenLogprob},
+    difficulty::Difficulty,
+    early_stopping::EarlyStoppingReport,
+    failure_analysis::FailureMode,
+    hooks::HookCommand,
+    judge::{FailureCategory, JudgePrompt},
+    model_config::ModelConfig,
+    output_guard::GenerationFailure,
+    scorer::ScorerConfig,
+};
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct EvalCase {
     pub input: String,
     pub expected_output: Option<ExpectedOutput>,
     pub metadata: HashMap<String, String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub scorer: Option<ScorerConfig>,
+    /// Runs before the case, e.g. to reset state the case's tools touch.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub setup: Option<HookCommand>,
+    /// Runs after the case, even when it fails or the run stops before the
+    /// case finishes.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub teardown: Option<HookCommand>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -47,6 +68,32 @@ pub struct EvalResult {
     pub iterations: Vec<IterationResult>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub pass_at_k: Option<PassAtKStats>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub difficulty: Option<Difficulty>,
+    /// Mean token log probability across every iteration's output, when captured.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mean_logprob: Option<f64>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub notes: Vec<Note>,
+}
+
+/// A freeform remark attached to a run or a case, e.g. "after retrieval fix".
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Note {
+    pub text: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub author: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl Note {
+    pub fn new(text: impl Into<String>, author: Option<String>) -> Self {
+        Self {
+            text: text.into(),
+            author,
+            created_at: Utc::now(),
+        }
+    }
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -54,7 +101,26 @@ pub struct IterationResult {
     pub actual_output: String,
     pub judge_score: f64,
     pub judge_reasoning: String,
+    /// The judge's full structured output, kept when its tool schema is customised.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub judge_output: Option<serde_json::Value>,
     pub passed: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub failure_category: Option<FailureCategory>,
+    /// Stop reason, serving model, token usage and latency of the generation.
+    #[serde(flatten)]
+    pub generation: GenerationMetadata,
+    /// The output was cut off by the token limit, even after any retries.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub truncated: bool,
+    /// Token limit the output was generated with, when raised after truncation.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_tokens: Option<u32>,
+    /// Set when the output was rejected before judging; the score is then 0.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub generation_failure: Option<GenerationFailure>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub logprobs: Option<Vec<TokenLogprob>>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -69,15 +135,81 @@ pub struct EvalCaseReport {
     pub input: String,
     pub expected_output: Option<ExpectedOutputObject>,
     pub metadata: HashMap<String, String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub scorer: Option<ScorerConfig>,
+}
+
+impl EvalCaseReport {
+    /// The case's `id` metadata, or else a prefix of the SHA-256 of its input,
+    /// which stays the same across runs of the suite.
+    pub fn id(&self) -> String {
+        match self.metadata.get("id") {
+            Some(id) => id.clone(),
+            None => format!("{:x}", Sha256::digest(self.input.as_bytes()))[..12].to_string(),
+        }
+    }
+}
+
+impl From<&EvalCaseReport> for EvalCase {
+    fn from(case: &EvalCaseReport) -> Self {
+        Self {
+            input: case.input.clone(),
+            expected_output: case.expected_output.clone().map(ExpectedOutput::Object),
+            metadata: case.metadata.clone(),
+            scorer: case.scorer.clone(),
+            setup: None,
+            teardown: None,
+        }
+    }
 }
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct EvaluationReport {
+    /// See [`crate::schema::SCHEMA_VERSION`].
+    #[serde(default)]
+    pub schema_version: u32,
     pub metadata: ReportMetadata,
     pub configuration: ModelConfig,
     pub judge_configuration: JudgePrompt,
     pub summary: ReportSummary,
     pub results: Vec<EvalResult>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub failure_modes: Vec<FailureMode>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub early_stopping: Option<EarlyStoppingReport>,
+}
+
+impl EvaluationReport {
+    /// Attaches a note to the case with `case_id`, or to the run itself.
+    pub fn annotate(&mut self, case_id: Option<&str>, note: Note) -> Result<()> {
+        let Some(case_id) = case_id else {
+            self.metadata.notes.push(note);
+            return Ok(());
+        };
+
+        let result = self
+            .results
+            .iter_mut()
+            .find(|result| result.case.id() == case_id)
+            .ok_or_else(|| anyhow!("No case with id '{}' in the report", case_id))?;
+        result.notes.push(note);
+        Ok(())
+    }
+
+    /// Mean generation latency across every iteration of every case.
+    pub fn mean_latency_seconds(&self) -> f64 {
+        let latencies: Vec<f64> = self
+            .results
+            .iter()
+            .flat_map(|result| result.iterations.iter())
+            .map(|iteration| iteration.generation.latency_seconds)
+            .collect();
+        if latencies.is_empty() {
+            0.0
+        } else {
+            latencies.iter().sum::<f64>() / latencies.len() as f64
+        }
+    }
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -86,6 +218,38 @@ pub struct ReportMetadata {
     pub total_cases: usize,
     pub threshold: f64,
     pub execution_time_seconds: f64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub suite: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub system_prompt: Option<PromptVersion>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub judge_prompt: Option<PromptVersion>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub judge_model: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub notes: Vec<Note>,
+}
+
+/// Identifies the exact prompt a run used.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct PromptVersion {
+    /// SHA-256 of the prompt text.
+    pub hash: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub version: Option<String>,
+}
+
+impl PromptVersion {
+    pub fn new(prompt: &str) -> Self {
+        Self {
+            hash: format!("{:x}", Sha256::digest(prompt.as_bytes())),
+            version: None,
+        }
+    }
+
+    pub fn short_hash(&self) -> &str {
+        &self.hash[..self.hash.len().min(12)]
+    }
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -97,6 +261,21 @@ pub struct ReportSummary {
     pub min_score: f64,
     pub max_score: f64,
     pub category_breakdown: HashMap<String, CategoryStats>,
+    /// Pass rates by the values of each `--breakdown-by` metadata key that
+    /// at least one case has.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub breakdowns: BTreeMap<String, HashMap<String, CategoryStats>>,
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub difficulty_breakdown: HashMap<String, CategoryStats>,
+    /// Iterations whose output was rejected before judging.
+    #[serde(default)]
+    pub generation_failures: usize,
+    /// Iterations whose output was cut off by the token limit.
+    #[serde(default)]
+    pub truncated: usize,
+    /// Failing iterations per judge failure category.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub failure_categories: BTreeMap<FailureCategory, usize>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -0,0 +1,29 @@
+pub mod braintrust;
+pub mod langsmith;
+
+use anyhow::Result;
+
+use crate::{evaluation::EvaluationReport, transport::HttpConfig};
+
+#[async_trait::async_trait]
+pub trait Exporter: Send + Sync {
+    async fn export(&self, report: &EvaluationReport) -> Result<()>;
+}
+
+pub fn create_exporter(target: &str, http: &HttpConfig) -> Result<Box<dyn Exporter>> {
+    match target {
+        "braintrust" => Ok(Box::new(braintrust::BraintrustExporter::new(
+            http.client()?,
+        )?)),
+        "langsmith" => Ok(Box::new(langsmith::LangSmithExporter::new(http.client()?)?)),
+        _ => Err(anyhow::anyhow!("Unsupported export target: {}", target)),
+    }
+}
+
+pub(crate) fn experiment_name(report: &EvaluationReport) -> String {
+    format!(
+        "{}-{}",
+        report.configuration.model,
+        report.metadata.generated_at.format("%Y%m%dT%H%M%S")
+    )
+}
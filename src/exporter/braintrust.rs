@@ -0,0 +1,110 @@
+use anyhow::{Result, anyhow};
+
+use super::{Exporter, experiment_name};
+use crate::evaluation::EvaluationReport;
+
+const API_URL: &str = "https://api.braintrust.dev/v1";
+
+pub struct BraintrustExporter {
+    client: reqwest::Client,
+    api_key: String,
+    project: String,
+}
+
+impl BraintrustExporter {
+    pub fn new(client: reqwest::Client) -> Result<Self> {
+        let api_key = std::env::var("BRAINTRUST_API_KEY")
+            .map_err(|_| anyhow!("BRAINTRUST_API_KEY environment variable not set"))?;
+        let project = std::env::var("BRAINTRUST_PROJECT").unwrap_or_else(|_| "evals".to_string());
+        Ok(Self {
+            client,
+            api_key,
+            project,
+        })
+    }
+
+    async fn post(&self, path: &str, body: serde_json::Value) -> Result<serde_json::Value> {
+        let response = self
+            .client
+            .post(format!("{API_URL}{path}"))
+            .bearer_auth(&self.api_key)
+            .json(&body)
+            .send()
+            .await?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let text = response.text().await.unwrap_or_default();
+            return Err(anyhow!(
+                "Braintrust request to {} failed ({}): {}",
+                path,
+                status,
+                text
+            ));
+        }
+
+        Ok(response.json().await?)
+    }
+}
+
+#[async_trait::async_trait]
+impl Exporter for BraintrustExporter {
+    async fn export(&self, report: &EvaluationReport) -> Result<()> {
+        let project = self
+            .post("/project", serde_json::json!({ "name": self.project }))
+            .await?;
+        let project_id = project["id"]
+            .as_str()
+            .ok_or_else(|| anyhow!("Braintrust project response is missing an id"))?;
+
+        let experiment = self
+            .post(
+                "/experiment",
+                serde_json::json!({
+                    "project_id": project_id,
+                    "name": experiment_name(report),
+                    "metadata": {
+                        "configuration": report.configuration,
+                        "threshold": report.metadata.threshold,
+                    }
+                }),
+            )
+            .await?;
+        let experiment_id = experiment["id"]
+            .as_str()
+            .ok_or_else(|| anyhow!("Braintrust experiment response is missing an id"))?;
+
+        let events: Vec<serde_json::Value> = report
+            .results
+            .iter()
+            .flat_map(|result| {
+                result
+                    .iterations
+                    .iter()
+                    .enumerate()
+                    .map(move |(iteration, output)| {
+                        serde_json::json!({
+                            "input": result.case.input,
+                            "output": output.actual_output,
+                            "expected": result.case.expected_output,
+                            "scores": { "judge": output.judge_score },
+                            "metadata": {
+                                "case": result.case.metadata,
+                                "iteration": iteration,
+                                "passed": output.passed,
+                                "judge_reasoning": output.judge_reasoning,
+                            }
+                        })
+                    })
+            })
+            .collect();
+
+        self.post(
+            &format!("/experiment/{experiment_id}/insert"),
+            serde_json::json!({ "events": events }),
+        )
+        .await?;
+
+        Ok(())
+    }
+}
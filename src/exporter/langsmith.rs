@@ -0,0 +1,104 @@
+use anyhow::{Result, anyhow};
+use uuid::Uuid;
+
+use super::{Exporter, experiment_name};
+use crate::evaluation::EvaluationReport;
+
+pub struct LangSmithExporter {
+    client: reqwest::Client,
+    api_key: String,
+    endpoint: String,
+    project: String,
+}
+
+impl LangSmithExporter {
+    pub fn new(client: reqwest::Client) -> Result<Self> {
+        let api_key = std::env::var("LANGSMITH_API_KEY")
+            .map_err(|_| anyhow!("LANGSMITH_API_KEY environment variable not set"))?;
+        let endpoint = std::env::var("LANGSMITH_ENDPOINT")
+            .unwrap_or_else(|_| "https://api.smith.langchain.com".to_string());
+        let project = std::env::var("LANGSMITH_PROJECT").unwrap_or_else(|_| "evals".to_string());
+        Ok(Self {
+            client,
+            api_key,
+            endpoint,
+            project,
+        })
+    }
+
+    async fn post(&self, path: &str, body: serde_json::Value) -> Result<()> {
+        let response = self
+            .client
+            .post(format!("{}{}", self.endpoint.trim_end_matches('/'), path))
+            .header("x-api-key", &self.api_key)
+            .json(&body)
+            .send()
+            .await?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let text = response.text().await.unwrap_or_default();
+            return Err(anyhow!(
+                "LangSmith request to {} failed ({}): {}",
+                path,
+                status,
+                text
+            ));
+        }
+
+        Ok(())
+    }
+}
+
+#[async_trait::async_trait]
+impl Exporter for LangSmithExporter {
+    async fn export(&self, report: &EvaluationReport) -> Result<()> {
+        let experiment = experiment_name(report);
+        let timestamp = report.metadata.generated_at.to_rfc3339();
+
+        for result in &report.results {
+            for (iteration, output) in result.iterations.iter().enumerate() {
+                let run_id = Uuid::new_v4();
+
+                self.post(
+                    "/runs",
+                    serde_json::json!({
+                        "id": run_id,
+                        "name": experiment,
+                        "run_type": "llm",
+                        "session_name": self.project,
+                        "start_time": timestamp,
+                        "end_time": timestamp,
+                        "inputs": {
+                            "input": result.case.input,
+                            "expected_output": result.case.expected_output,
+                        },
+                        "outputs": { "output": output.actual_output },
+                        "extra": {
+                            "metadata": {
+                                "case": result.case.metadata,
+                                "iteration": iteration,
+                                "provider": report.configuration.provider,
+                                "model": report.configuration.model,
+                            }
+                        }
+                    }),
+                )
+                .await?;
+
+                self.post(
+                    "/feedback",
+                    serde_json::json!({
+                        "run_id": run_id,
+                        "key": "judge_score",
+                        "score": output.judge_score,
+                        "comment": output.judge_reasoning,
+                    }),
+                )
+                .await?;
+            }
+        }
+
+        Ok(())
+    }
+}
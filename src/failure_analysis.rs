@@ -0,0 +1,152 @@
+use std::sync::Arc;
+
+use anyhow::{Result, anyhow};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    clustering::cluster_by_similarity,
+    conversation_model::{ConversationConifg, ConversationModel, GenerationResult, ToolDefinition},
+    embedding_model::EmbeddingModel,
+    evaluation::EvalResult,
+    model_config::ModelConfig,
+};
+
+const MAX_EXAMPLES_PER_CLUSTER: usize = 8;
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct FailureMode {
+    pub theme: String,
+    pub description: String,
+    pub case_count: usize,
+    pub cases: Vec<String>,
+}
+
+pub struct FailureAnalyzer {
+    embedding_model: Arc<dyn EmbeddingModel>,
+    labeling_model: Arc<dyn ConversationModel>,
+    similarity_threshold: f32,
+}
+
+impl FailureAnalyzer {
+    pub fn new(
+        embedding_model: Arc<dyn EmbeddingModel>,
+        labeling_model: Arc<dyn ConversationModel>,
+        similarity_threshold: f32,
+    ) -> Self {
+        Self {
+            embedding_model,
+            labeling_model,
+            similarity_threshold,
+        }
+    }
+
+    /// Clusters failed cases by their judge reasoning and labels each cluster
+    /// with a failure theme, most frequent first.
+    pub async fn analyze(&self, results: &[EvalResult]) -> Result<Vec<FailureMode>> {
+        let failed: Vec<&EvalResult> = results.iter().filter(|result| !result.passed).collect();
+        if failed.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let reasonings: Vec<String> = failed
+            .iter()
+            .map(|result| {
+                result
+                    .iterations
+                    .iter()
+                    .map(|iteration| iteration.judge_reasoning.as_str())
+                    .collect::<Vec<_>>()
+                    .join("\n")
+            })
+            .collect();
+
+        let embeddings = self.embedding_model.embed(&reasonings).await?;
+        let clusters = cluster_by_similarity(&embeddings, self.similarity_threshold);
+
+        let mut failure_modes = Vec::with_capacity(clusters.len());
+        for cluster in clusters {
+            let examples: Vec<String> = cluster
+                .iter()
+                .take(MAX_EXAMPLES_PER_CLUSTER)
+                .map(|&index| {
+                    format!(
+                        "Input: {}\nJudge reasoning: {}",
+                        failed[index].case.input, reasonings[index]
+                    )
+                })
+                .collect();
+
+            let (theme, description) = self.label(&examples).await?;
+            failure_modes.push(FailureMode {
+                theme,
+                description,
+                case_count: cluster.len(),
+                cases: cluster
+                    .iter()
+                    .map(|&index| failed[index].case.input.clone())
+                    .collect(),
+            });
+        }
+
+        failure_modes.sort_by_key(|failure_mode| std::cmp::Reverse(failure_mode.case_count));
+        Ok(failure_modes)
+    }
+
+    async fn label(&self, examples: &[String]) -> Result<(String, String)> {
+        let label_tool = ToolDefinition {
+            name: "label_failure_mode".to_string(),
+            description: "Name the failure mode shared by a group of failed evaluation cases"
+                .to_string(),
+            schema: serde_json::json!({
+                "type": "object",
+                "properties": {
+                    "theme": {
+                        "type": "string",
+                        "description": "Short name of the shared failure mode (a few words)"
+                    },
+                    "description": {
+                        "type": "string",
+                        "description": "One or two sentences describing what goes wrong"
+                    }
+                },
+                "required": ["theme", "description"]
+            }),
+        };
+
+        let config = ModelConfig {
+            temperature: Some(0.0),
+            system: Some("You analyze failed AI evaluation cases. You must use the label_failure_mode tool to name the failure mode they have in common.".into()),
+            tools: Some(vec![label_tool]),
+            ..ModelConfig::default()
+        };
+
+        let internal_config =
+            ConversationConifg::new(config).with_forced_tool("label_failure_mode".to_string());
+
+        let prompt = format!(
+            "These evaluation cases failed for similar reasons:\n\n{}",
+            examples.join("\n\n---\n\n")
+        );
+
+        let results = self
+            .labeling_model
+            .generate(&prompt, &internal_config)
+            .await?
+            .results;
+        for result in results {
+            if let GenerationResult::ToolUse { arguments, .. } = result {
+                let theme = arguments["theme"]
+                    .as_str()
+                    .unwrap_or("Unlabeled")
+                    .to_string();
+                let description = arguments["description"]
+                    .as_str()
+                    .unwrap_or_default()
+                    .to_string();
+                return Ok((theme, description));
+            }
+        }
+
+        Err(anyhow!("Expected tool use response from labeling model"))
+    }
+}
@@ -0,0 +1,130 @@
+use std::path::PathBuf;
+
+use anyhow::{Result, anyhow};
+
+use crate::{evaluation::EvaluationReport, model_config::ModelConfig};
+
+pub const DEFAULT_HISTORY_DIR: &str = ".evals/history";
+
+/// Stores reports on disk as `<root>/<suite>/<timestamp>-<provider>-<model>.json`.
+pub struct HistoryStore {
+    root: PathBuf,
+}
+
+impl HistoryStore {
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self { root: root.into() }
+    }
+
+    pub async fn save(&self, report: &EvaluationReport) -> Result<PathBuf> {
+        let suite = report
+            .metadata
+            .suite
+            .as_deref()
+            .ok_or_else(|| anyhow!("Reports need a suite name to be stored in history"))?;
+
+        let suite_dir = self.suite_dir(suite)?;
+        tokio::fs::create_dir_all(&suite_dir).await?;
+
+        let file_name = format!(
+            "{}-{}-{}.json",
+            report.metadata.generated_at.format("%Y%m%dT%H%M%S%.3fZ"),
+            report.configuration.provider,
+            sanitize(&report.configuration.model)
+        );
+        let path = suite_dir.join(file_name);
+        tokio::fs::write(&path, serde_json::to_string_pretty(report)?).await?;
+
+        Ok(path)
+    }
+
+    fn suite_dir(&self, suite: &str) -> Result<PathBuf> {
+        check_suite_name(suite)?;
+        Ok(self.root.join(sanitize(suite)))
+    }
+
+    pub async fn suites(&self) -> Result<Vec<String>> {
+        let mut suites = Vec::new();
+        let mut entries = match tokio::fs::read_dir(&self.root).await {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(suites),
+            Err(e) => return Err(e.into()),
+        };
+
+        while let Some(entry) = entries.next_entry().await? {
+            if entry.file_type().await?.is_dir() {
+                suites.push(entry.file_name().to_string_lossy().to_string());
+            }
+        }
+        suites.sort();
+
+        Ok(suites)
+    }
+
+    /// Loads the most recent stored report of `suite` from the provider and
+    /// model of `configuration`, if any.
+    pub async fn latest(
+        &self,
+        suite: &str,
+        configuration: &ModelConfig,
+    ) -> Result<Option<EvaluationReport>> {
+        if !self.suites().await?.contains(&sanitize(suite)) {
+            return Ok(None);
+        }
+        Ok(self
+            .load_suite(suite)
+            .await?
+            .into_iter()
+            .rev()
+            .find(|report| {
+                report.configuration.provider == configuration.provider
+                    && report.configuration.model == configuration.model
+            }))
+    }
+
+    /// Loads every stored report of `suite`, oldest first.
+    pub async fn load_suite(&self, suite: &str) -> Result<Vec<EvaluationReport>> {
+        let suite_dir = self.suite_dir(suite)?;
+        let mut entries = tokio::fs::read_dir(&suite_dir)
+            .await
+            .map_err(|e| anyhow!("No history for suite '{}': {}", suite, e))?;
+
+        let mut reports = Vec::new();
+        while let Some(entry) = entries.next_entry().await? {
+            let path = entry.path();
+            if path
+                .extension()
+                .is_some_and(|extension| extension == "json")
+            {
+                let content = tokio::fs::read_to_string(&path).await?;
+                let report = EvaluationReport::from_json(&content)
+                    .map_err(|e| anyhow!("Failed to load '{}': {}", path.display(), e))?;
+                reports.push(report);
+            }
+        }
+        reports.sort_by_key(|report| report.metadata.generated_at);
+
+        Ok(reports)
+    }
+}
+
+/// Refuses suite names that would not name a directory inside the history,
+/// such as `..`.
+pub fn check_suite_name(suite: &str) -> Result<()> {
+    if suite.chars().all(|c| c == '.') {
+        return Err(anyhow!("Invalid suite name '{}'", suite));
+    }
+    Ok(())
+}
+
+fn sanitize(name: &str) -> String {
+    name.chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '-' || c == '_' || c == '.' {
+                c
+            } else {
+                '-'
+            }
+        })
+        .collect()
+}
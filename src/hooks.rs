@@ -0,0 +1,131 @@
+use std::{process::Stdio, time::Duration};
+
+use anyhow::{Result, anyhow};
+use serde::{Deserialize, Serialize};
+use tokio::{io::AsyncWriteExt, process::Command};
+
+use crate::{
+    evaluation::{EvalResult, EvaluationReport},
+    model_config::ModelConfig,
+};
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct HooksConfig {
+    #[serde(default)]
+    pub pre_run: Vec<HookCommand>,
+    #[serde(default)]
+    pub post_case: Vec<HookCommand>,
+    #[serde(default)]
+    pub post_run: Vec<HookCommand>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct HookCommand {
+    pub command: String,
+    #[serde(default)]
+    pub timeout_seconds: Option<u64>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(tag = "event")]
+pub enum HookEvent<'a> {
+    #[serde(rename = "pre_run")]
+    PreRun {
+        configuration: &'a ModelConfig,
+        total_cases: usize,
+    },
+    #[serde(rename = "post_case")]
+    PostCase { result: &'a EvalResult },
+    #[serde(rename = "post_run")]
+    PostRun { report: &'a EvaluationReport },
+}
+
+pub struct Hooks {
+    config: HooksConfig,
+}
+
+impl Hooks {
+    pub fn new(config: HooksConfig) -> Self {
+        Self { config }
+    }
+
+    pub async fn dispatch(&self, event: &HookEvent<'_>) -> Result<()> {
+        let commands = match event {
+            HookEvent::PreRun { .. } => &self.config.pre_run,
+            HookEvent::PostCase { .. } => &self.config.post_case,
+            HookEvent::PostRun { .. } => &self.config.post_run,
+        };
+
+        if commands.is_empty() {
+            return Ok(());
+        }
+
+        let payload = serde_json::to_vec(event)?;
+        for hook in commands {
+            run_shell_command(
+                &hook.command,
+                &payload,
+                hook.timeout_seconds.map(Duration::from_secs),
+            )
+            .await?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Runs `command` through `sh -c`, writing `payload` to its stdin.
+pub async fn run_shell_command(
+    command: &str,
+    payload: &[u8],
+    timeout: Option<Duration>,
+) -> Result<()> {
+    let mut child = Command::new("sh")
+        .arg("-c")
+        .arg(command)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true)
+        .spawn()
+        .map_err(|e| anyhow!("Failed to spawn '{}': {}", command, e))?;
+
+    // Written concurrently with draining stderr, so a payload larger than the
+    // pipe buffer can't block a command that doesn't read it. Commands that
+    // never read stdin close the pipe early; that is not an error.
+    let writer = child.stdin.take().map(|mut stdin| {
+        let payload = payload.to_vec();
+        tokio::spawn(async move {
+            let _ = stdin.write_all(&payload).await;
+        })
+    });
+
+    let output = match timeout {
+        // On timeout the child is dropped, which kills it.
+        Some(timeout) => match tokio::time::timeout(timeout, child.wait_with_output()).await {
+            Ok(output) => output?,
+            Err(_) => {
+                if let Some(writer) = writer {
+                    writer.abort();
+                }
+                return Err(anyhow!(
+                    "Command '{}' timed out after {:?}",
+                    command,
+                    timeout
+                ));
+            }
+        },
+        None => child.wait_with_output().await?,
+    };
+
+    if !output.status.success() {
+        return Err(anyhow!(
+            "Command '{}' exited with {}: {}",
+            command,
+            output.status,
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+
+    Ok(())
+}
@@ -0,0 +1,14 @@
+pub mod openai_evals;
+pub mod promptfoo;
+
+use anyhow::Result;
+
+use crate::evaluation::EvalCase;
+
+pub fn import_cases(format: &str, content: &str) -> Result<Vec<EvalCase>> {
+    match format {
+        "promptfoo" => promptfoo::import(content),
+        "openai-evals" => openai_evals::import(content),
+        _ => Err(anyhow::anyhow!("Unsupported import format: {}", format)),
+    }
+}
@@ -0,0 +1,79 @@
+use std::collections::HashMap;
+
+use anyhow::{Result, anyhow};
+use serde::Deserialize;
+
+use crate::evaluation::{EvalCase, ExpectedOutput, ExpectedOutputObject};
+
+#[derive(Debug, Deserialize)]
+struct OpenAIEvalsSample {
+    input: OpenAIEvalsInput,
+    #[serde(default)]
+    ideal: Option<OpenAIEvalsIdeal>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum OpenAIEvalsInput {
+    Text(String),
+    Messages(Vec<OpenAIEvalsMessage>),
+}
+
+#[derive(Debug, Deserialize)]
+struct OpenAIEvalsMessage {
+    role: String,
+    content: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum OpenAIEvalsIdeal {
+    Single(String),
+    Many(Vec<String>),
+}
+
+/// Converts an OpenAI Evals JSONL dataset into cases, one per sample line.
+pub fn import(content: &str) -> Result<Vec<EvalCase>> {
+    content
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(index, line)| {
+            let sample: OpenAIEvalsSample = serde_json::from_str(line)
+                .map_err(|e| anyhow!("Invalid sample on line {}: {}", index + 1, e))?;
+
+            let input = match sample.input {
+                OpenAIEvalsInput::Text(text) => text,
+                OpenAIEvalsInput::Messages(messages) => match messages.as_slice() {
+                    [message] if message.role == "user" => message.content.clone(),
+                    _ => messages
+                        .iter()
+                        .map(|message| format!("{}: {}", message.role, message.content))
+                        .collect::<Vec<_>>()
+                        .join("\n\n"),
+                },
+            };
+
+            let expected_output = sample.ideal.map(|ideal| match ideal {
+                OpenAIEvalsIdeal::Single(text) => ExpectedOutput::String(text),
+                OpenAIEvalsIdeal::Many(answers) if answers.len() == 1 => {
+                    ExpectedOutput::String(answers[0].clone())
+                }
+                OpenAIEvalsIdeal::Many(answers) => {
+                    ExpectedOutput::Object(ExpectedOutputObject::ContentComparison {
+                        description: format!("Any of: {}", answers.join(" | ")),
+                    })
+                }
+            });
+
+            Ok(EvalCase {
+                input,
+                expected_output,
+                metadata: HashMap::from([("source".to_string(), "openai-evals".to_string())]),
+                scorer: None,
+                setup: None,
+                teardown: None,
+            })
+        })
+        .collect()
+}
@@ -0,0 +1,181 @@
+use std::collections::HashMap;
+
+use anyhow::{Result, anyhow};
+use serde::Deserialize;
+
+use crate::evaluation::{EvalCase, ExpectedOutput, ExpectedOutputObject};
+
+#[derive(Debug, Deserialize)]
+struct PromptfooConfig {
+    #[serde(default)]
+    prompts: Vec<PromptfooPrompt>,
+    #[serde(default)]
+    tests: Vec<PromptfooTest>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum PromptfooPrompt {
+    Raw(String),
+    Labeled { raw: String },
+}
+
+#[derive(Debug, Deserialize)]
+struct PromptfooTest {
+    #[serde(default)]
+    description: Option<String>,
+    #[serde(default)]
+    vars: HashMap<String, serde_yaml::Value>,
+    #[serde(default)]
+    assert: Vec<PromptfooAssertion>,
+    #[serde(default)]
+    metadata: HashMap<String, serde_yaml::Value>,
+}
+
+#[derive(Debug, Deserialize)]
+struct PromptfooAssertion {
+    #[serde(rename = "type")]
+    assertion_type: String,
+    #[serde(default)]
+    value: Option<serde_yaml::Value>,
+}
+
+/// Converts a promptfoo configuration into cases, rendering every prompt
+/// against every test's variables.
+pub fn import(content: &str) -> Result<Vec<EvalCase>> {
+    let config: PromptfooConfig = serde_yaml::from_str(content)?;
+
+    let prompts: Vec<String> = if config.prompts.is_empty() {
+        vec!["{{prompt}}".to_string()]
+    } else {
+        config
+            .prompts
+            .into_iter()
+            .map(|prompt| match prompt {
+                PromptfooPrompt::Raw(raw) | PromptfooPrompt::Labeled { raw } => raw,
+            })
+            .collect()
+    };
+
+    if let Some(prompt) = prompts.iter().find(|prompt| prompt.starts_with("file://")) {
+        return Err(anyhow!(
+            "File-based prompts are not supported, inline '{}' first",
+            prompt
+        ));
+    }
+
+    let mut cases = Vec::new();
+    for test in &config.tests {
+        let vars: HashMap<&str, String> = test
+            .vars
+            .iter()
+            .map(|(name, value)| (name.as_str(), yaml_to_string(value)))
+            .collect();
+
+        let mut metadata: HashMap<String, String> = test
+            .metadata
+            .iter()
+            .map(|(key, value)| (key.clone(), yaml_to_string(value)))
+            .collect();
+        metadata.insert("source".to_string(), "promptfoo".to_string());
+        if let Some(description) = &test.description {
+            metadata.insert("description".to_string(), description.clone());
+        }
+
+        for prompt in &prompts {
+            cases.push(EvalCase {
+                input: render(prompt, &vars),
+                expected_output: expected_output(&test.assert, &vars),
+                metadata: metadata.clone(),
+                scorer: None,
+                setup: None,
+                teardown: None,
+            });
+        }
+    }
+
+    Ok(cases)
+}
+
+fn render(template: &str, vars: &HashMap<&str, String>) -> String {
+    let mut rendered = template.to_string();
+    for (name, value) in vars {
+        rendered = rendered
+            .replace(&format!("{{{{{name}}}}}"), value)
+            .replace(&format!("{{{{ {name} }}}}"), value);
+    }
+    rendered
+}
+
+fn expected_output(
+    assertions: &[PromptfooAssertion],
+    vars: &HashMap<&str, String>,
+) -> Option<ExpectedOutput> {
+    if let [assertion] = assertions
+        && assertion.assertion_type == "equals"
+    {
+        return assertion
+            .value
+            .as_ref()
+            .map(|value| ExpectedOutput::String(render(&yaml_to_string(value), vars)));
+    }
+
+    let mut is_behavior = false;
+    let descriptions: Vec<String> = assertions
+        .iter()
+        .map(|assertion| {
+            let value = assertion
+                .value
+                .as_ref()
+                .map(|value| render(&yaml_to_string(value), vars))
+                .unwrap_or_default();
+
+            match assertion.assertion_type.as_str() {
+                "equals" | "similar" => value,
+                "contains" | "icontains" => format!("Contains \"{value}\""),
+                "not-contains" | "not-icontains" => format!("Does not contain \"{value}\""),
+                "contains-any" => format!("Contains any of: {value}"),
+                "contains-all" => format!("Contains all of: {value}"),
+                "starts-with" => format!("Starts with \"{value}\""),
+                "regex" => format!("Matches the regular expression /{value}/"),
+                "is-json" => {
+                    is_behavior = true;
+                    "Responds with valid JSON".to_string()
+                }
+                "llm-rubric" | "model-graded-closedqa" | "g-eval" => {
+                    is_behavior = true;
+                    value
+                }
+                other => {
+                    is_behavior = true;
+                    format!("Satisfies the promptfoo '{other}' assertion: {value}")
+                }
+            }
+        })
+        .collect();
+
+    if descriptions.is_empty() {
+        return None;
+    }
+
+    let description = descriptions.join("\n");
+    Some(ExpectedOutput::Object(if is_behavior {
+        ExpectedOutputObject::BehaviorDescription { description }
+    } else {
+        ExpectedOutputObject::ContentComparison { description }
+    }))
+}
+
+fn yaml_to_string(value: &serde_yaml::Value) -> String {
+    match value {
+        serde_yaml::Value::String(text) => text.clone(),
+        serde_yaml::Value::Sequence(items) => items
+            .iter()
+            .map(yaml_to_string)
+            .collect::<Vec<_>>()
+            .join(", "),
+        other => serde_yaml::to_string(other)
+            .map(|text| text.trim().to_string())
+            .unwrap_or_default(),
+    }
+}
@@ -1,25 +1,99 @@
-use std::sync::Arc;
+use std::{collections::BTreeMap, fmt, sync::Arc};
 
 use anyhow::{Result, anyhow};
 use serde::{Deserialize, Serialize};
 
 use crate::{
-    conversation_model::{ConversationModel, GenerationResult, ConversationConifg, ToolDefinition},
-    evaluation::{EvalCase, ExpectedOutput, ExpectedOutputObject},
-    model_config::ModelConfig,
+    conversation_model::{ConversationConifg, ConversationModel, GenerationResult, ToolDefinition},
+    evaluation::{EvalCase, EvalResult, ExpectedOutput, ExpectedOutputObject},
+    model_config::{ModelConfig, Provider},
+    scorer::Score,
 };
 
+pub const JUDGE_MODEL: &str = "claude-3-5-sonnet-20241022";
+
+const FAILURE_CATEGORY_DESCRIPTION: &str =
+    "Main way the response falls short. Required when the score is below the pass threshold";
+
+/// How a response failed, as classified by the judge.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum FailureCategory {
+    Hallucination,
+    Refusal,
+    Formatting,
+    Incompleteness,
+    OffTopic,
+    Other,
+}
+
+impl FailureCategory {
+    pub const ALL: [FailureCategory; 6] = [
+        FailureCategory::Hallucination,
+        FailureCategory::Refusal,
+        FailureCategory::Formatting,
+        FailureCategory::Incompleteness,
+        FailureCategory::OffTopic,
+        FailureCategory::Other,
+    ];
+}
+
+impl fmt::Display for FailureCategory {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FailureCategory::Hallucination => write!(f, "hallucination"),
+            FailureCategory::Refusal => write!(f, "refusal"),
+            FailureCategory::Formatting => write!(f, "formatting"),
+            FailureCategory::Incompleteness => write!(f, "incompleteness"),
+            FailureCategory::OffTopic => write!(f, "off_topic"),
+            FailureCategory::Other => write!(f, "other"),
+        }
+    }
+}
+
+/// Counts failing iterations by the failure category the judge assigned.
+pub fn failure_category_counts(results: &[EvalResult]) -> BTreeMap<FailureCategory, usize> {
+    let mut counts = BTreeMap::new();
+    for category in results
+        .iter()
+        .flat_map(|result| result.iterations.iter())
+        .filter_map(|iteration| iteration.failure_category)
+    {
+        *counts.entry(category).or_insert(0) += 1;
+    }
+    counts
+}
+
 pub struct JudgeModel {
     model: Arc<dyn ConversationModel>,
+    model_name: String,
     prompt: JudgePrompt,
+    threshold: Option<f64>,
 }
 
 impl JudgeModel {
     pub fn new(model: Arc<dyn ConversationModel>, prompt: JudgePrompt) -> Self {
-        Self { model, prompt }
+        Self {
+            model,
+            model_name: JUDGE_MODEL.to_string(),
+            prompt,
+            threshold: None,
+        }
+    }
+
+    /// Judges with `model_name` instead of [`JUDGE_MODEL`].
+    pub fn with_model_name(mut self, model_name: impl Into<String>) -> Self {
+        self.model_name = model_name.into();
+        self
     }
 
-    pub async fn evaluate(&self, case: &EvalCase, actual_output: &str) -> Result<(f64, String)> {
+    /// Tells the judge the pass threshold, below which it must categorise the failure.
+    pub fn with_threshold(mut self, threshold: f64) -> Self {
+        self.threshold = Some(threshold);
+        self
+    }
+
+    pub async fn evaluate(&self, case: &EvalCase, actual_output: &str) -> Result<Score> {
         let (expected_text, evaluation_type) = match &case.expected_output {
             Some(ExpectedOutput::String(content)) => (content.as_str(), "content"),
             Some(ExpectedOutput::Object(ExpectedOutputObject::ContentComparison {
@@ -42,40 +116,34 @@ impl JudgeModel {
         let eval_tool = ToolDefinition {
             name: "evaluate_response".to_string(),
             description: "Evaluate the quality of a response and provide a score".to_string(),
-            schema: serde_json::json!({
-                "type": "object",
-                "properties": {
-                    "score": {
-                        "type": "number",
-                        "minimum": 0.0,
-                        "maximum": 1.0,
-                        "description": "Quality score from 0.0 to 1.0"
-                    },
-                    "reasoning": {
-                        "type": "string",
-                        "description": "Detailed reasoning for the score"
-                    }
-                },
-                "required": ["score", "reasoning"]
-            }),
+            schema: self.tool_schema(),
         };
 
         let judge_config = ModelConfig {
-            provider: "anthropic".to_string(),
-            model: "claude-3-5-sonnet-20241022".to_string(),
+            provider: Provider::Anthropic,
+            model: self.model_name.clone(),
             max_tokens: 1000,
+            max_tokens_cap: None,
             temperature: Some(0.0),
             top_k: None,
             top_p: None,
-            system: Some(self.prompt.system.clone()),
+            system: Some(self.prompt.system.clone().into()),
             tools: Some(vec![eval_tool]),
+            builtin_tools: Vec::new(),
             iterations: None,
+            session: None,
+            logprobs: None,
+            extra_params: serde_json::Value::Null,
         };
 
         let internal_config =
             ConversationConifg::new(judge_config).with_forced_tool("evaluate_response".to_string());
 
-        let results = self.model.generate(&prompt_text, &internal_config).await?;
+        let results = self
+            .model
+            .generate(&prompt_text, &internal_config)
+            .await?
+            .results;
 
         for result in results {
             match result {
@@ -85,7 +153,16 @@ impl JudgeModel {
                         .as_str()
                         .unwrap_or("No reasoning provided")
                         .to_string();
-                    return Ok((score, reasoning));
+                    // Left unset when the judge omits it rather than guessed.
+                    let failure_category =
+                        serde_json::from_value(arguments["failure_category"].clone()).ok();
+                    let details = self.prompt.tool_schema.is_some().then_some(arguments);
+                    return Ok(Score {
+                        score,
+                        reasoning,
+                        failure_category,
+                        details,
+                    });
                 }
                 _ => continue,
             }
@@ -97,12 +174,92 @@ impl JudgeModel {
     pub fn prompt(&self) -> &JudgePrompt {
         &self.prompt
     }
+
+    pub fn model_name(&self) -> &str {
+        &self.model_name
+    }
+
+    fn tool_schema(&self) -> serde_json::Value {
+        let mut schema = self.prompt.tool_schema();
+        if let Some(threshold) = self.threshold
+            && let Some(description) =
+                schema.pointer_mut("/properties/failure_category/description")
+            && description == FAILURE_CATEGORY_DESCRIPTION
+        {
+            *description = serde_json::Value::String(format!(
+                "Main way the response falls short. Required when the score is below {}",
+                threshold
+            ));
+        }
+        schema
+    }
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct JudgePrompt {
     pub system: String,
     pub user_template: String,
+    /// Replaces the input schema of the `evaluate_response` tool, e.g. to
+    /// require extra fields such as a severity. It must keep `score` and
+    /// `reasoning`; the judge's full output is then kept in each result.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tool_schema: Option<serde_json::Value>,
+}
+
+impl JudgePrompt {
+    /// The judge prompt as a single text, for hashing and diffing.
+    pub fn text(&self) -> String {
+        match &self.tool_schema {
+            Some(schema) => format!("{}\n\n{}\n\n{:#}", self.system, self.user_template, schema),
+            None => format!("{}\n\n{}", self.system, self.user_template),
+        }
+    }
+
+    pub fn tool_schema(&self) -> serde_json::Value {
+        self.tool_schema.clone().unwrap_or_else(|| {
+            serde_json::json!({
+                "type": "object",
+                "properties": {
+                    "score": {
+                        "type": "number",
+                        "minimum": 0.0,
+                        "maximum": 1.0,
+                        "description": "Quality score from 0.0 to 1.0"
+                    },
+                    "reasoning": {
+                        "type": "string",
+                        "description": "Detailed reasoning for the score"
+                    },
+                    "failure_category": {
+                        "type": "string",
+                        "enum": FailureCategory::ALL,
+                        "description": FAILURE_CATEGORY_DESCRIPTION
+                    }
+                },
+                "required": ["score", "reasoning"]
+            })
+        })
+    }
+
+    pub fn validate(&self) -> Result<()> {
+        let Some(schema) = &self.tool_schema else {
+            return Ok(());
+        };
+
+        if schema["type"] != "object" {
+            return Err(anyhow!("Judge tool schema must be of type \"object\""));
+        }
+        for field in ["score", "reasoning"] {
+            if !schema["properties"][field].is_object() {
+                return Err(anyhow!(
+                    "Judge tool schema must define the \"{}\" property",
+                    field
+                ));
+            }
+        }
+
+        Ok(())
+    }
 }
 
 impl Default for JudgePrompt {
@@ -110,6 +267,7 @@ impl Default for JudgePrompt {
         Self {
             system: "You are an AI judge evaluating response quality. You must use the evaluate_response tool to provide your assessment. Consider the evaluation type when scoring.".to_string(),
             user_template: "Evaluate this response:\n\nInput: {input}\nExpected: {expected}\nActual: {actual}\nEvaluation Type: {evaluation_type}\n\nEvaluation Instructions:\n- If evaluation_type is 'content': Compare the actual output against the expected content. The actual output should convey the same meaning/information as expected, but doesn't need to be word-for-word identical.\n- If evaluation_type is 'behavior': Assess whether the actual output demonstrates the described behavior. The expected text describes how the model should behave, not what it should output.\n- If evaluation_type is 'none': Evaluate the general quality and appropriateness of the response.\n\nUse the evaluate_response tool to provide your score (0.0-1.0) and reasoning.".to_string(),
+            tool_schema: None,
         }
     }
 }
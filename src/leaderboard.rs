@@ -0,0 +1,98 @@
+use std::collections::HashMap;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::{evaluation::EvaluationReport, model_config::Provider, pricing::PriceTable};
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Leaderboard {
+    pub suite: String,
+    pub entries: Vec<LeaderboardEntry>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct LeaderboardEntry {
+    pub rank: usize,
+    pub provider: Provider,
+    pub model: String,
+    pub pass_rate_percent: f64,
+    pub average_score: f64,
+    /// Cost of the run's generations, when the model has a price.
+    pub cost_usd: Option<f64>,
+    pub mean_latency_seconds: f64,
+    pub total_cases: usize,
+    pub generated_at: DateTime<Utc>,
+}
+
+impl Leaderboard {
+    /// Ranks the latest report of every provider and model by pass rate, then
+    /// by average score.
+    pub fn from_reports(suite: &str, reports: &[EvaluationReport], prices: &PriceTable) -> Self {
+        let mut latest: HashMap<(Provider, &str), &EvaluationReport> = HashMap::new();
+        for report in reports {
+            let key = (
+                report.configuration.provider,
+                report.configuration.model.as_str(),
+            );
+            match latest.get(&key) {
+                Some(existing)
+                    if existing.metadata.generated_at >= report.metadata.generated_at => {}
+                _ => {
+                    latest.insert(key, report);
+                }
+            }
+        }
+
+        let mut entries: Vec<LeaderboardEntry> = latest
+            .into_values()
+            .map(|report| LeaderboardEntry {
+                rank: 0,
+                provider: report.configuration.provider,
+                model: report.configuration.model.clone(),
+                pass_rate_percent: report.summary.pass_rate_percent,
+                average_score: report.summary.average_score,
+                cost_usd: prices.report_cost(report),
+                mean_latency_seconds: report.mean_latency_seconds(),
+                total_cases: report.metadata.total_cases,
+                generated_at: report.metadata.generated_at,
+            })
+            .collect();
+
+        entries.sort_by(|a, b| {
+            b.pass_rate_percent
+                .total_cmp(&a.pass_rate_percent)
+                .then(b.average_score.total_cmp(&a.average_score))
+        });
+        for (index, entry) in entries.iter_mut().enumerate() {
+            entry.rank = index + 1;
+        }
+
+        Self {
+            suite: suite.to_string(),
+            entries,
+        }
+    }
+
+    pub fn to_markdown(&self) -> String {
+        let mut markdown = format!(
+            "## {}\n\n| # | Model | Pass rate | Avg score | Cost | Latency |\n|---|---|---|---|---|---|\n",
+            self.suite
+        );
+        for entry in &self.entries {
+            markdown.push_str(&format!(
+                "| {} | {}/{} | {:.1}% | {:.2} | {} | {:.2}s |\n",
+                entry.rank,
+                entry.provider,
+                entry.model,
+                entry.pass_rate_percent,
+                entry.average_score,
+                entry
+                    .cost_usd
+                    .map_or_else(|| "—".to_string(), |cost| format!("${cost:.4}")),
+                entry.mean_latency_seconds
+            ));
+        }
+        markdown
+    }
+}
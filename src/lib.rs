@@ -0,0 +1,38 @@
+pub mod cases_source;
+pub mod clustering;
+pub mod compare;
+pub mod conversation_model;
+pub mod daemon;
+pub mod dedupe;
+pub mod difficulty;
+pub mod early_stopping;
+pub mod embedding_model;
+pub mod evaluation;
+pub mod exporter;
+pub mod failure_analysis;
+pub mod history;
+pub mod hooks;
+pub mod importer;
+pub mod judge;
+pub mod leaderboard;
+pub mod manifest;
+pub mod mcp_manager;
+pub mod model_config;
+pub mod output_guard;
+pub mod preprocess;
+pub mod pricing;
+pub mod rejudge;
+pub mod report_output;
+pub mod rescore;
+pub mod runner;
+pub mod schema;
+pub mod scorer;
+pub mod server;
+pub mod session;
+pub mod snapshot;
+pub mod suggest;
+pub mod suite;
+pub mod sweep;
+pub mod tested_model;
+pub mod threshold_tuning;
+pub mod transport;
@@ -1,30 +1,52 @@
-mod conversation_model;
-mod evaluation;
-mod judge;
-mod mcp_manager;
-mod model_config;
-mod tested_model;
 mod ui;
 
-use std::{collections::HashMap, sync::Arc};
+use std::sync::Arc;
 
 use anyhow::{Result, anyhow};
 use chrono::Utc;
-use clap::{Parser, Subcommand};
+use clap::{Args, Parser, Subcommand};
+use tokio_stream::StreamExt;
 
-use futures::stream::FuturesUnordered;
-use tokio_stream::{Stream, StreamExt};
-
-use crate::{
-    conversation_model::create_model,
-    evaluation::{
-        CategoryStats, EvalCase, EvalCaseReport, EvalResult, EvaluationReport, ReportMetadata,
-        ReportSummary, IterationResult, PassAtKStats,
-    },
-    judge::{JudgeModel, JudgePrompt},
+use evals::{
+    cases_source::read_cases_source,
+    compare::compare_reports,
+    conversation_model::create_model_with_http_config,
+    daemon::{Daemon, DaemonConfig},
+    dedupe::dedupe_cases,
+    difficulty::DifficultyEstimator,
+    early_stopping::EarlyStopping,
+    embedding_model::create_embedding_model_with_http_config,
+    evaluation::{EvalCase, EvaluationReport, Note},
+    exporter::create_exporter,
+    failure_analysis::FailureAnalyzer,
+    history::{DEFAULT_HISTORY_DIR, HistoryStore},
+    hooks::{HookEvent, Hooks, HooksConfig},
+    importer::import_cases,
+    judge::{JUDGE_MODEL, JudgeModel, JudgePrompt},
+    leaderboard::Leaderboard,
+    manifest::{RecordedFile, RunManifest},
     mcp_manager::{McpManager, McpServersConfig},
-    model_config::ModelConfig,
+    model_config::{ModelConfig, Provider, SystemBlock, SystemPrompt},
+    pricing::PriceTable,
+    rejudge::rejudge_stream,
+    report_output::write_output,
+    rescore::{HistoryRescorer, RescoredTrend},
+    runner::EvalRun,
+    runner::{generate_report, metadata_breakdowns},
+    schema::SCHEMA_VERSION,
+    scorer::ScorerConfig,
+    scorer::Scorers,
+    server::{ServerPolicy, serve},
+    snapshot::{SnapshotFile, SnapshotMatcher, compare_snapshots, record_outputs},
+    suggest::PromptSuggester,
+    suite::Suite,
+    sweep::Sweep,
+    sweep::SweepParameter,
+    sweep::SweepPoint,
+    sweep::range_values,
     tested_model::TestedModel,
+    threshold_tuning::{HumanLabel, sweep_thresholds},
+    transport::{CassetteMode, HttpConfig},
 };
 
 /// Command-line interface for the AI evaluation tool
@@ -34,278 +56,829 @@ use crate::{
 pub struct Cli {
     #[command(subcommand)]
     pub command: Commands,
+    #[command(flatten)]
+    pub http: HttpArgs,
+}
+
+/// Network settings for every outbound request
+#[derive(Args)]
+pub struct HttpArgs {
+    /// Proxy URL for every outbound request
+    #[arg(long, global = true, env = "EVALS_PROXY")]
+    proxy: Option<String>,
+    /// PEM bundle of additional root certificates to trust
+    #[arg(long, global = true, env = "EVALS_CA_BUNDLE")]
+    ca_bundle: Option<String>,
+    /// Base URL of the Anthropic API
+    #[arg(long, global = true, env = "ANTHROPIC_BASE_URL")]
+    anthropic_base_url: Option<String>,
+    /// Base URL of the OpenAI API
+    #[arg(long, global = true, env = "OPENAI_BASE_URL")]
+    openai_base_url: Option<String>,
+}
+
+impl HttpArgs {
+    fn into_config(self) -> HttpConfig {
+        HttpConfig {
+            proxy: self.proxy,
+            ca_bundle: self.ca_bundle.map(Into::into),
+            anthropic_base_url: self.anthropic_base_url,
+            openai_base_url: self.openai_base_url,
+            cassettes: None,
+        }
+    }
+}
+
+/// Model and generation settings shared by the commands that query a model
+#[derive(Args)]
+pub struct ModelArgs {
+    /// AI provider to use (e.g., "anthropic", "openai", "openai-responses")
+    #[arg(long)]
+    provider: Provider,
+    /// Model name to evaluate
+    #[arg(long)]
+    model: String,
+    /// Maximum tokens to generate (default: 1000)
+    #[arg(long)]
+    max_tokens: Option<u32>,
+    /// Retry outputs cut off by the token limit with double the limit, up to this cap
+    #[arg(long)]
+    max_tokens_cap: Option<u32>,
+    /// Temperature for text generation (0.0-1.0)
+    #[arg(long)]
+    temperature: Option<f64>,
+    /// Top-k sampling parameter
+    #[arg(long)]
+    top_k: Option<u32>,
+    /// Top-p (nucleus) sampling parameter (0.0-1.0)
+    #[arg(long)]
+    top_p: Option<f64>,
+    /// System prompt (use @filename to load from file); repeat to compose it from several blocks
+    #[arg(long)]
+    system: Vec<String>,
+    /// Capture token log probabilities with this many top alternatives (0-20, OpenAI only)
+    #[arg(long)]
+    logprobs: Option<u32>,
+    /// JSON object merged into the provider request body (use @filename to load from file)
+    #[arg(long)]
+    extra_params: Option<String>,
+    /// Built-in tool for openai-responses, as a type ("web_search") or a JSON object (repeatable)
+    #[arg(long = "builtin-tool")]
+    builtin_tools: Vec<String>,
+}
+
+impl ModelArgs {
+    async fn into_config(self, iterations: Option<usize>) -> Result<ModelConfig> {
+        let system = load_system(self.system).await?;
+
+        let extra_params = match self.extra_params {
+            Some(extra_params) => {
+                let extra_params = match extra_params.strip_prefix('@') {
+                    Some(file_path) => tokio::fs::read_to_string(file_path).await.map_err(|e| {
+                        anyhow!("Failed to read extra params file '{}': {}", file_path, e)
+                    })?,
+                    None => extra_params,
+                };
+                let extra_params: serde_json::Value = serde_json::from_str(&extra_params)
+                    .map_err(|e| anyhow!("Invalid --extra-params JSON: {}", e))?;
+                if !extra_params.is_object() {
+                    return Err(anyhow!("--extra-params must be a JSON object"));
+                }
+                extra_params
+            }
+            None => serde_json::Value::Null,
+        };
+
+        let builtin_tools = self
+            .builtin_tools
+            .into_iter()
+            .map(|tool| {
+                if tool.trim_start().starts_with('{') {
+                    serde_json::from_str(&tool)
+                        .map_err(|e| anyhow!("Invalid --builtin-tool JSON: {}", e))
+                } else {
+                    Ok(serde_json::json!({ "type": tool }))
+                }
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        let config = ModelConfig {
+            provider: self.provider,
+            model: self.model,
+            max_tokens: self.max_tokens.unwrap_or(1000),
+            max_tokens_cap: self.max_tokens_cap,
+            temperature: self.temperature,
+            top_k: self.top_k,
+            top_p: self.top_p,
+            system,
+            tools: None,
+            builtin_tools,
+            iterations,
+            session: None,
+            logprobs: self.logprobs,
+            extra_params,
+        };
+        config.validate()?;
+        Ok(config)
+    }
 }
 
 /// Available commands for the evaluation tool
+#[allow(clippy::large_enum_variant)]
 #[derive(Subcommand)]
 pub enum Commands {
     /// Run evaluations on a set of test cases
     Run {
-        /// Path to JSON file containing evaluation cases
+        /// Path or HTTP(S) URL of the JSON file containing evaluation cases
+        #[arg(long, required_unless_present = "from_manifest")]
+        cases_file: Option<String>,
+        /// Extra HTTP header for remote cases files, as "Name: value" (repeatable)
+        #[arg(long = "cases-header")]
+        cases_headers: Vec<String>,
+        /// Run setup/teardown commands and scorers from a remote cases file
         #[arg(long)]
-        cases_file: String,
+        allow_case_commands: bool,
         /// Minimum score threshold for passing evaluations (default: 0.8)
         #[arg(long)]
         threshold: Option<f64>,
         /// Judge model to use for evaluation (default: claude-3-5-sonnet-20241022)
         #[arg(long)]
         judge_model: Option<String>,
-        /// AI provider to use (e.g., "anthropic", "openai")
+        /// JSON file with the judge "system" prompt, "user_template" and optional "tool_schema"
         #[arg(long)]
-        provider: String,
-        /// Model name to evaluate
+        judge_prompt: Option<String>,
+        #[command(flatten)]
+        model: Option<ModelArgs>,
+        /// Number of iterations to run for pass@k evaluation (default: 1)
         #[arg(long)]
-        model: String,
-        /// Maximum tokens to generate (default: 1000)
+        iterations: Option<usize>,
+        /// Write a manifest of everything needed to reproduce the run to this path
         #[arg(long)]
-        max_tokens: Option<u32>,
-        /// Temperature for text generation (0.0-1.0)
+        manifest: Option<String>,
+        /// Repeat the run recorded in a manifest, refusing to run if its inputs changed
+        #[arg(long, conflicts_with_all = [
+            "cases_file", "ModelArgs", "threshold", "judge_model", "judge_prompt", "iterations",
+            "mcp_servers", "scorer", "hooks", "record_cassettes", "replay_cassettes",
+            "early_stop_margin", "breakdown_by",
+        ])]
+        from_manifest: Option<String>,
+        /// Output path for evaluation report (JSON format); s3:// and gs:// URLs are uploaded
         #[arg(long)]
-        temperature: Option<f64>,
-        /// Top-k sampling parameter
+        output: Option<String>,
+        /// Path to MCP servers configuration file
         #[arg(long)]
-        top_k: Option<u32>,
-        /// Top-p (nucleus) sampling parameter (0.0-1.0)
+        mcp_servers: Option<String>,
+        /// Path to a WASM scorer module used instead of the judge model
         #[arg(long)]
-        top_p: Option<f64>,
-        /// Number of iterations to run for pass@k evaluation (default: 1)
+        scorer: Option<String>,
+        /// Path to lifecycle hooks configuration file
         #[arg(long)]
-        iterations: Option<usize>,
-        /// System prompt (use @filename to load from file)
+        hooks: Option<String>,
+        /// Suite name used to group runs in history (default: the cases file name)
         #[arg(long)]
-        system: Option<String>,
-        /// Output file path for evaluation report (JSON format)
+        suite: Option<String>,
+        /// Version label recorded with the system prompt hash in the report
+        #[arg(long)]
+        prompt_version: Option<String>,
+        /// Version label recorded with the judge prompt hash in the report
+        #[arg(long)]
+        judge_prompt_version: Option<String>,
+        /// Store the report in the run history
+        #[arg(long)]
+        save_history: bool,
+        /// Directory of the run history (default: .evals/history)
+        #[arg(long)]
+        history_dir: Option<String>,
+        /// Previous report used to estimate case difficulty (repeatable)
+        #[arg(long = "difficulty-from")]
+        difficulty_reports: Vec<String>,
+        /// Cluster failed cases by judge reasoning and label the top failure modes
+        #[arg(long)]
+        analyze_failures: bool,
+        /// Similarity threshold for grouping failures (default: 0.85)
+        #[arg(long)]
+        failure_cluster_threshold: Option<f32>,
+        /// Export results to an external platform ("braintrust", "langsmith"; repeatable)
+        #[arg(long = "export")]
+        exports: Vec<String>,
+        /// Record provider requests and responses as cassettes in this directory
+        #[arg(long, conflicts_with = "replay_cassettes")]
+        record_cassettes: Option<String>,
+        /// Replay provider responses from cassettes in this directory instead of calling the APIs
+        #[arg(long)]
+        replay_cassettes: Option<String>,
+        /// Stop once the pass rate is known within this margin (e.g. 0.05 for ±5 points)
+        #[arg(long)]
+        early_stop_margin: Option<f64>,
+        /// Confidence level for early stopping (default: 0.95)
+        #[arg(long)]
+        early_stop_confidence: Option<f64>,
+        /// Minimum number of cases evaluated before stopping early (default: 30)
+        #[arg(long)]
+        early_stop_min_cases: Option<usize>,
+        /// Cases evaluated concurrently when early stopping is enabled (default: 8)
+        #[arg(long)]
+        early_stop_concurrency: Option<usize>,
+        /// Print failed cases with a word diff against their expected content
+        #[arg(long)]
+        verbose: bool,
+        /// Note stored with the run, e.g. "after retrieval fix" (repeatable)
+        #[arg(long = "note")]
+        notes: Vec<String>,
+        /// Metadata key to break the pass rate down by, e.g. "language" (repeatable)
+        #[arg(long = "breakdown-by")]
+        breakdown_by: Vec<String>,
+    },
+    /// Re-run only the judging phase over the outputs stored in a report
+    Rejudge {
+        /// Path to the evaluation report (JSON format)
+        report: String,
+        /// Minimum score threshold for passing (default: the report's threshold)
+        #[arg(long)]
+        threshold: Option<f64>,
+        /// Judge model to use (default: the report's judge model)
+        #[arg(long)]
+        judge_model: Option<String>,
+        /// JSON file with the judge "system" prompt, "user_template" and optional "tool_schema" (default: the report's)
+        #[arg(long)]
+        judge_prompt: Option<String>,
+        /// Version label recorded with the judge prompt hash in the report
+        #[arg(long)]
+        judge_prompt_version: Option<String>,
+        /// Print failed cases with a word diff against their expected content
+        #[arg(long)]
+        verbose: bool,
+        /// Output path for the new report (JSON format); s3:// and gs:// URLs are uploaded
         #[arg(long)]
         output: Option<String>,
+    },
+    /// Record blessed output snapshots, or diff new outputs against them
+    Snapshot {
+        /// Path or HTTP(S) URL of the JSON file containing evaluation cases
+        #[arg(long)]
+        cases_file: String,
+        /// Extra HTTP header for remote cases files, as "Name: value" (repeatable)
+        #[arg(long = "cases-header")]
+        cases_headers: Vec<String>,
+        #[command(flatten)]
+        model: ModelArgs,
+        /// Path to the snapshots file, recorded when it does not exist yet
+        #[arg(long)]
+        snapshots: String,
+        /// Overwrite the snapshots with the current outputs
+        #[arg(long)]
+        bless: bool,
+        /// How outputs are compared: "exact" or "judge" (default: exact)
+        #[arg(long)]
+        mode: Option<String>,
+        /// Minimum judge score for outputs to be considered equivalent (default: 0.8)
+        #[arg(long)]
+        threshold: Option<f64>,
+        /// Path to MCP servers configuration file
+        #[arg(long)]
+        mcp_servers: Option<String>,
+    },
+    /// Run the suite once per value of a model parameter and tabulate the scores
+    Sweep {
+        /// Path or HTTP(S) URL of the JSON file containing evaluation cases
+        #[arg(long)]
+        cases_file: String,
+        #[command(flatten)]
+        model: ModelArgs,
+        /// Parameter to vary ("temperature", "top_p", "max_tokens", "model")
+        #[arg(long)]
+        parameter: SweepParameter,
+        /// Comma-separated values to try
+        #[arg(
+            long,
+            value_delimiter = ',',
+            required_unless_present = "range",
+            conflicts_with = "range"
+        )]
+        values: Vec<String>,
+        /// Inclusive numeric range to try, as start:end:step
+        #[arg(long)]
+        range: Option<String>,
+        /// Minimum score threshold for passing evaluations (default: 0.8)
+        #[arg(long)]
+        threshold: Option<f64>,
+        /// Number of iterations to run for pass@k evaluation (default: 1)
+        #[arg(long)]
+        iterations: Option<usize>,
         /// Path to MCP servers configuration file
         #[arg(long)]
         mcp_servers: Option<String>,
+        /// Run setup/teardown commands and scorers from a remote cases file
+        #[arg(long)]
+        allow_case_commands: bool,
+        /// Output path for the sweep results (JSON format)
+        #[arg(long)]
+        output: Option<String>,
+    },
+    /// Attach a note to a report, or to one of its cases, in place
+    Annotate {
+        /// Path to the evaluation report (JSON format), e.g. a run in the history
+        report: String,
+        /// Case to annotate: its "id" metadata or the id shown with --verbose (default: the run)
+        #[arg(long)]
+        case_id: Option<String>,
+        /// Text of the note
+        #[arg(long)]
+        note: String,
+        /// Author recorded with the note
+        #[arg(long, env = "USER")]
+        author: Option<String>,
+    },
+    /// Compare two reports, showing score changes and prompt diffs
+    Compare {
+        /// Path to the baseline report (JSON format)
+        baseline: String,
+        /// Path to the candidate report (JSON format)
+        candidate: String,
+    },
+    /// Rank the latest run of every model in a suite's history
+    Leaderboard {
+        /// Suite to rank (default: every suite in the history)
+        #[arg(long)]
+        suite: Option<String>,
+        /// Directory of the run history (default: .evals/history)
+        #[arg(long)]
+        history_dir: Option<String>,
+        /// Output format: "terminal", "markdown" or "json" (default: terminal)
+        #[arg(long)]
+        format: Option<String>,
+        /// Output file path (default: stdout)
+        #[arg(long)]
+        output: Option<String>,
+        /// JSON file of model prices in USD per million tokens, added to the built-in prices
+        #[arg(long)]
+        prices: Option<String>,
+    },
+    /// Re-judge every stored run with one judge, recomputing trends without judge drift
+    RescoreHistory {
+        /// Judge model to re-score every run with
+        #[arg(long)]
+        judge: String,
+        /// JSON file with the judge "system" prompt, "user_template" and optional "tool_schema"
+        #[arg(long)]
+        judge_prompt: Option<String>,
+        /// Minimum score threshold for passing (default: each run's threshold)
+        #[arg(long)]
+        threshold: Option<f64>,
+        /// Suite to re-score (default: every suite in the history)
+        #[arg(long)]
+        suite: Option<String>,
+        /// Directory of the run history (default: .evals/history)
+        #[arg(long)]
+        history_dir: Option<String>,
+        /// Store the re-scored runs as a separate history in this directory
+        #[arg(long)]
+        output_dir: Option<String>,
+        /// Output path for the trends (JSON format); s3:// and gs:// URLs are uploaded
+        #[arg(long)]
+        output: Option<String>,
+    },
+    /// Run suites on a cron schedule, storing results in the run history and
+    /// notifying when metrics degrade
+    Daemon {
+        /// JSON file with the scheduled suites, tolerances and webhook
+        #[arg(long)]
+        config: String,
+    },
+    /// Serve a REST API to start runs, poll their status and fetch reports
+    Server {
+        /// Address to listen on (default: 127.0.0.1:8080)
+        #[arg(long)]
+        address: Option<std::net::SocketAddr>,
+        /// Bearer token required on every request
+        #[arg(long, env = "EVALS_SERVER_TOKEN")]
+        token: Option<String>,
+        /// Directory of the run history for runs started with "save_history" (default: .evals/history)
+        #[arg(long)]
+        history_dir: Option<String>,
+        /// Directory that "cases_file" paths and include tags may read from (default: inline cases only)
+        #[arg(long)]
+        cases_dir: Option<String>,
+        /// Accept cases with setup/teardown commands or WASM scorers, which run on the server
+        #[arg(long)]
+        allow_case_commands: bool,
+        /// Runs kept in memory before the oldest finished ones are dropped (default: 100)
+        #[arg(long)]
+        max_runs: Option<usize>,
+    },
+    /// Convert a promptfoo config or OpenAI Evals dataset into a cases file
+    Import {
+        /// Source format ("promptfoo", "openai-evals")
+        #[arg(long)]
+        format: String,
+        /// Path to the file to import
+        #[arg(long)]
+        input: String,
+        /// Output path for the converted cases file (default: stdout)
+        #[arg(long)]
+        output: Option<String>,
+    },
+    /// Remove near-duplicate cases using embedding similarity
+    Dedupe {
+        /// Path or HTTP(S) URL of the JSON file containing evaluation cases
+        #[arg(long)]
+        cases_file: String,
+        /// Cosine similarity above which cases are considered duplicates (default: 0.95)
+        #[arg(long)]
+        threshold: Option<f32>,
+        /// Embedding provider to use (default: openai)
+        #[arg(long)]
+        embedding_provider: Option<String>,
+        /// Embedding model to use (default: text-embedding-3-small)
+        #[arg(long)]
+        embedding_model: Option<String>,
+        /// Output path for the pruned cases file
+        #[arg(long)]
+        output: String,
+        /// Output path for the removed duplicates report (JSON format)
+        #[arg(long)]
+        report: Option<String>,
+    },
+    /// Suggest system prompt improvements from the failures in a report
+    Suggest {
+        /// Path to the evaluation report (JSON format)
+        #[arg(long)]
+        report: String,
+        /// System prompt to improve (use @filename to load from file; default: the report's)
+        #[arg(long)]
+        system: Option<String>,
+        /// Maximum number of failing cases to include (default: 10)
+        #[arg(long)]
+        sample: Option<usize>,
+        /// AI provider to use for suggestions (default: anthropic)
+        #[arg(long)]
+        provider: Option<Provider>,
+        /// Model to use for suggestions (default: claude-3-5-sonnet-20241022)
+        #[arg(long)]
+        model: Option<String>,
+        /// Output file path for the revised candidate prompt
+        #[arg(long)]
+        output_prompt: Option<String>,
+    },
+    /// Sweep the pass threshold against human labels and recommend a value
+    TuneThreshold {
+        /// Path to the evaluation report (JSON format)
+        #[arg(long)]
+        report: String,
+        /// Path to human labels (JSON array of {"input", "acceptable"}); default: "human_label" metadata
+        #[arg(long)]
+        labels: Option<String>,
+        /// Lowest threshold to evaluate (default: 0.0)
+        #[arg(long)]
+        min: Option<f64>,
+        /// Highest threshold to evaluate (default: 1.0)
+        #[arg(long)]
+        max: Option<f64>,
+        /// Sweep step (default: 0.05)
+        #[arg(long)]
+        step: Option<f64>,
+        /// Output file path for the sweep results (JSON format)
+        #[arg(long)]
+        output: Option<String>,
     },
 }
 
-fn run_eval_stream(
-    cases: Vec<EvalCase>,
-    tested_model: Arc<TestedModel>,
-    config: Arc<ModelConfig>,
-    judge: Arc<JudgeModel>,
-    threshold: f64,
-) -> impl Stream<Item = Result<EvalResult>> {
-    let futures: FuturesUnordered<_> = cases
-        .into_iter()
-        .map(|case| {
-            let tested_model = Arc::clone(&tested_model);
-            let config = Arc::clone(&config);
-            let judge = Arc::clone(&judge);
-
-            async move {
-                let iterations_count = config.iterations.unwrap_or(1);
-                
-                let mut iteration_results = Vec::new();
-                let mut passed_count = 0;
-                let mut total_score = 0.0;
-
-                for _ in 0..iterations_count {
-                    let actual_output = tested_model.respond(&case.input, &config).await?;
-                    let (judge_score, judge_reasoning) = judge.evaluate(&case, &actual_output).await?;
-                    let passed = judge_score >= threshold;
-                    
-                    if passed {
-                        passed_count += 1;
-                    }
-                    total_score += judge_score;
-
-                    iteration_results.push(IterationResult {
-                        actual_output,
-                        judge_score,
-                        judge_reasoning,
-                        passed,
-                    });
-                }
-
-                let average_score = total_score / iterations_count as f64;
-                let overall_passed = passed_count > 0; // Pass if any iteration passes
-
-                let case_report = EvalCaseReport {
-                    input: case.input.clone(),
-                    expected_output: case.expected_output.as_ref().and_then(|e| e.to_object()),
-                    metadata: case.metadata.clone(),
-                };
-
-                let pass_at_k_stats = if iterations_count > 1 {
-                    Some(PassAtKStats {
-                        total_iterations: iterations_count,
-                        passed_iterations: passed_count,
-                        pass_rate: passed_count as f64 / iterations_count as f64,
-                    })
-                } else {
-                    None
-                };
-
-                Ok(EvalResult {
-                    case: case_report,
-                    judge_score: average_score,
-                    passed: overall_passed,
-                    iterations: iteration_results,
-                    pass_at_k: pass_at_k_stats,
-                })
-            }
-        })
-        .collect();
+fn suite_name(cases_file: &str) -> String {
+    let file_name = cases_file.rsplit('/').next().unwrap_or(cases_file);
+    std::path::Path::new(file_name)
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().to_string())
+        .unwrap_or_else(|| file_name.to_string())
+}
 
-    futures
+async fn load_system_prompt(system: String) -> Result<String> {
+    if let Some(file_path) = system.strip_prefix('@') {
+        tokio::fs::read_to_string(file_path)
+            .await
+            .map_err(|e| anyhow!("Failed to read system prompt file '{}': {}", file_path, e))
+    } else {
+        Ok(system)
+    }
 }
 
-fn generate_report(
-    results: &[EvalResult],
-    config: &ModelConfig,
-    judge_prompt: &JudgePrompt,
-    threshold: f64,
-    execution_time: f64,
-) -> Result<EvaluationReport> {
-    let total_cases = results.len();
-    let passed_count = results.iter().filter(|r| r.passed).count();
-    let failed_count = total_cases - passed_count;
-    let pass_rate = (passed_count as f64 / total_cases as f64) * 100.0;
-
-    let scores: Vec<f64> = results.iter().map(|r| r.judge_score).collect();
-    let average_score = scores.iter().sum::<f64>() / scores.len() as f64;
-    let min_score = scores.iter().fold(f64::INFINITY, |a, &b| a.min(b));
-    let max_score = scores.iter().fold(f64::NEG_INFINITY, |a, &b| a.max(b));
-
-    let mut category_breakdown = HashMap::new();
-    for result in results {
-        if let Some(category) = result.case.metadata.get("category") {
-            let entry = category_breakdown
-                .entry(category.clone())
-                .or_insert(CategoryStats {
-                    total: 0,
-                    passed: 0,
-                    pass_rate_percent: 0.0,
-                });
-            entry.total += 1;
-            if result.passed {
-                entry.passed += 1;
-            }
-            entry.pass_rate_percent = (entry.passed as f64 / entry.total as f64) * 100.0;
-        }
+/// Composes the system prompt from `--system` arguments, one block per argument.
+async fn load_system(sources: Vec<String>) -> Result<Option<SystemPrompt>> {
+    let mut blocks = Vec::with_capacity(sources.len());
+    for system_str in sources {
+        blocks.push(load_system_prompt(system_str).await?);
     }
+    Ok(match blocks.len() {
+        0 => None,
+        1 => blocks.pop().map(SystemPrompt::Text),
+        _ => Some(SystemPrompt::Blocks(
+            blocks.into_iter().map(SystemBlock::new).collect(),
+        )),
+    })
+}
 
-    let report = EvaluationReport {
-        metadata: ReportMetadata {
-            generated_at: Utc::now(),
-            total_cases,
-            threshold,
-            execution_time_seconds: execution_time,
-        },
-        configuration: config.clone(),
-        judge_configuration: judge_prompt.clone(),
-        summary: ReportSummary {
-            passed_count,
-            failed_count,
-            pass_rate_percent: pass_rate,
-            average_score,
-            min_score,
-            max_score,
-            category_breakdown,
-        },
-        results: results.to_vec(),
-    };
-
-    Ok(report)
+async fn read_report(path: &str) -> Result<EvaluationReport> {
+    let report_content = tokio::fs::read_to_string(path)
+        .await
+        .map_err(|e| anyhow!("Failed to read report '{}': {}", path, e))?;
+    EvaluationReport::from_json(&report_content)
+        .map_err(|e| anyhow!("Failed to read report '{}': {}", path, e))
+}
+
+async fn load_judge_prompt(path: &str) -> Result<JudgePrompt> {
+    let content = tokio::fs::read_to_string(path)
+        .await
+        .map_err(|e| anyhow!("Failed to read judge prompt '{}': {}", path, e))?;
+    let judge_prompt: JudgePrompt = serde_json::from_str(&content)
+        .map_err(|e| anyhow!("Invalid judge prompt '{}': {}", path, e))?;
+    judge_prompt.validate()?;
+    Ok(judge_prompt)
 }
 
 #[tokio::main]
 async fn main() -> Result<()> {
     let cli = Cli::parse();
+    let http = cli.http.into_config();
 
     match cli.command {
         Commands::Run {
             cases_file,
+            cases_headers,
+            allow_case_commands,
             threshold,
             judge_model,
-            provider,
+            judge_prompt,
             model,
-            max_tokens,
-            temperature,
-            top_k,
-            top_p,
             iterations,
-            system,
+            manifest,
+            from_manifest,
             output,
             mcp_servers,
+            scorer,
+            hooks,
+            exports,
+            suite,
+            prompt_version,
+            judge_prompt_version,
+            save_history,
+            history_dir,
+            difficulty_reports,
+            analyze_failures,
+            failure_cluster_threshold,
+            record_cassettes,
+            replay_cassettes,
+            early_stop_margin,
+            early_stop_confidence,
+            early_stop_min_cases,
+            early_stop_concurrency,
+            verbose,
+            notes,
+            breakdown_by,
         } => {
-            let threshold = threshold.unwrap_or(0.8);
             let start_time = std::time::Instant::now();
 
-            let cases_content = std::fs::read_to_string(&cases_file)?;
-            let cases: Vec<EvalCase> = serde_json::from_str(&cases_content)?;
+            let recorded = match &from_manifest {
+                Some(path) => Some(RunManifest::load(path).await?),
+                None => None,
+            };
+            let cases_file = cases_file
+                .or_else(|| {
+                    recorded
+                        .as_ref()
+                        .map(|recorded| recorded.cases_file.clone())
+                })
+                .ok_or_else(|| anyhow!("--cases-file is required"))?;
+            let threshold = threshold
+                .or(recorded.as_ref().map(|recorded| recorded.threshold))
+                .unwrap_or(0.8);
 
-            let system_prompt = if let Some(system_str) = system {
-                if system_str.starts_with('@') {
-                    let file_path = &system_str[1..];
-                    Some(tokio::fs::read_to_string(file_path).await.map_err(|e| {
-                        anyhow!("Failed to read system prompt file '{}': {}", file_path, e)
-                    })?)
-                } else {
-                    Some(system_str)
-                }
-            } else {
-                None
+            let cases_content = read_cases_source(&cases_file, &cases_headers, &http).await?;
+            let mut suite_config = Suite::from_json(&cases_content)?;
+            suite_config.check_case_commands(&cases_file, allow_case_commands)?;
+            suite_config.preprocess(&cases_file).await?;
+
+            // A rerun reads the system prompt, judge prompt, MCP servers and hooks
+            // from disk again, so the drift check compares them with the recording.
+            let system_sources = match (&recorded, &model) {
+                (Some(recorded), _) => recorded.system_sources.clone(),
+                (None, Some(model)) => model.system.clone(),
+                (None, None) => Vec::new(),
             };
+            let mut config = match (&recorded, model) {
+                (Some(recorded), _) => ModelConfig {
+                    system: load_system(system_sources.clone()).await?,
+                    ..recorded.configuration.clone()
+                },
+                (None, Some(model)) => model.into_config(iterations).await?,
+                (None, None) => return Err(anyhow!("--provider and --model are required")),
+            };
+            config.session = suite_config.session.clone();
 
-            let config = ModelConfig {
-                provider: provider.clone(),
-                model,
-                max_tokens: max_tokens.unwrap_or(1000),
-                temperature,
-                top_k,
-                top_p,
-                system: system_prompt,
-                tools: None,
-                iterations,
+            let judge_prompt_file = judge_prompt.or(recorded
+                .as_ref()
+                .and_then(|recorded| recorded.judge_prompt_file.clone()));
+            let judge_prompt = match &judge_prompt_file {
+                Some(path) => load_judge_prompt(path).await?,
+                None => JudgePrompt::default(),
+            };
+            let judge_model_name = judge_model
+                .or(recorded
+                    .as_ref()
+                    .map(|recorded| recorded.judge_model.clone()))
+                .unwrap_or_else(|| JUDGE_MODEL.to_string());
+
+            let mcp_servers = mcp_servers.or(recorded
+                .as_ref()
+                .and_then(|recorded| recorded.mcp_servers.as_ref())
+                .map(|file| file.path.clone()));
+            let mcp_config_content =
+                match &mcp_servers {
+                    Some(path) => Some(tokio::fs::read_to_string(path).await.map_err(|e| {
+                        anyhow!("Failed to read MCP servers config '{}': {}", path, e)
+                    })?),
+                    None => None,
+                };
+            let hooks = hooks.or(recorded
+                .as_ref()
+                .and_then(|recorded| recorded.hooks.as_ref())
+                .map(|file| file.path.clone()));
+            let hooks_content = match &hooks {
+                Some(path) => Some(
+                    tokio::fs::read_to_string(path)
+                        .await
+                        .map_err(|e| anyhow!("Failed to read hooks config '{}': {}", path, e))?,
+                ),
+                None => None,
             };
 
-            let conversation_model = create_model(&provider)?;
+            let breakdown_by = match &recorded {
+                Some(recorded) => recorded.breakdown_by.clone(),
+                None => breakdown_by,
+            };
+            let mut breakdown_keys = suite_config.breakdown_by.clone();
+            for key in &breakdown_by {
+                if !breakdown_keys.contains(key) {
+                    breakdown_keys.push(key.clone());
+                }
+            }
 
-            let mcp_manager = if let Some(mcp_config_path) = mcp_servers {
-                let mcp_config_content = tokio::fs::read_to_string(&mcp_config_path).await?;
+            let early_stopping = early_stop_margin
+                .map(|margin| {
+                    let mut early_stopping = EarlyStopping::new(margin);
+                    if let Some(confidence) = early_stop_confidence {
+                        early_stopping.confidence = confidence;
+                    }
+                    if let Some(min_cases) = early_stop_min_cases {
+                        early_stopping.min_cases = min_cases;
+                    }
+                    if let Some(concurrency) = early_stop_concurrency {
+                        early_stopping.concurrency = concurrency;
+                    }
+                    early_stopping
+                })
+                .or(recorded
+                    .as_ref()
+                    .and_then(|recorded| recorded.early_stopping));
+            let scorer = scorer.map(|path| ScorerConfig::Wasm { path }).or(recorded
+                .as_ref()
+                .and_then(|recorded| recorded.scorer.clone()));
+            let seed = recorded
+                .as_ref()
+                .map_or_else(rand::random, |recorded| recorded.seed);
+
+            let cassettes = record_cassettes
+                .map(CassetteMode::Record)
+                .or(replay_cassettes.map(CassetteMode::Replay))
+                .or(recorded
+                    .as_ref()
+                    .and_then(|recorded| recorded.cassettes.clone())
+                    .map(CassetteMode::Replay));
+
+            let mut run_manifest = RunManifest::new(
+                &cases_file,
+                &cases_content,
+                &suite_config.cases,
+                config.clone(),
+                judge_prompt.clone(),
+                threshold,
+            );
+            run_manifest.system_sources = system_sources;
+            run_manifest.judge_model = judge_model_name.clone();
+            run_manifest.judge_prompt_file = judge_prompt_file;
+            run_manifest.mcp_servers = mcp_servers
+                .as_deref()
+                .zip(mcp_config_content.as_deref())
+                .map(|(path, content)| RecordedFile::new(path, content));
+            run_manifest.hooks = hooks
+                .as_deref()
+                .zip(hooks_content.as_deref())
+                .map(|(path, content)| RecordedFile::new(path, content));
+            run_manifest.breakdown_by = breakdown_by;
+            run_manifest.seed = seed;
+            run_manifest.early_stopping = early_stopping;
+            run_manifest.scorer = scorer.clone();
+            run_manifest.cassettes = cassettes.as_ref().map(|cassettes| match cassettes {
+                CassetteMode::Record(dir) | CassetteMode::Replay(dir) => dir.clone(),
+            });
+            run_manifest.hash_files(&suite_config.cases).await?;
+            if let (Some(path), Some(recorded)) = (&from_manifest, &recorded) {
+                let drift = recorded.drift(&run_manifest);
+                if !drift.is_empty() {
+                    return Err(anyhow!(
+                        "Inputs drifted from manifest '{}':\n  {}",
+                        path,
+                        drift.join("\n  ")
+                    ));
+                }
+            }
+            if let Some(path) = &manifest {
+                run_manifest.save(path).await?;
+            }
+
+            let http = HttpConfig { cassettes, ..http };
+            let judge_conversation_model =
+                create_model_with_http_config(Provider::Anthropic, &http)?;
+
+            let mut run_builder = EvalRun::builder(config.clone())
+                .suite(suite.unwrap_or_else(|| suite_name(&cases_file)))
+                .cases(suite_config.cases)
+                .categories(suite_config.categories)
+                .breakdown_by(breakdown_keys.clone())
+                .threshold(threshold)
+                .model(create_model_with_http_config(config.provider, &http)?)
+                .judge_model(Arc::clone(&judge_conversation_model))
+                .judge_model_name(judge_model_name)
+                .judge_prompt(judge_prompt)
+                .seed(seed);
+
+            if let Some(mcp_config_content) = mcp_config_content {
                 let mcp_config: McpServersConfig = serde_json::from_str(&mcp_config_content)?;
-                Some(Arc::new(
+                run_builder = run_builder.mcp_manager(Arc::new(
                     McpManager::start_servers(&mcp_config.servers).await?,
+                ));
+            }
+
+            if let Some(scorer) = scorer {
+                run_builder = run_builder.scorer(scorer);
+            }
+
+            if let Some(prompt_version) = prompt_version {
+                run_builder = run_builder.prompt_version(prompt_version);
+            }
+
+            if let Some(judge_prompt_version) = judge_prompt_version {
+                run_builder = run_builder.judge_prompt_version(judge_prompt_version);
+            }
+
+            if let Some(early_stopping) = early_stopping {
+                run_builder = run_builder.early_stopping(early_stopping);
+            }
+
+            let eval_run = run_builder.build()?;
+
+            let failure_analyzer = if analyze_failures {
+                Some(FailureAnalyzer::new(
+                    create_embedding_model_with_http_config(
+                        "openai",
+                        "text-embedding-3-small",
+                        &http,
+                    )?,
+                    Arc::clone(&judge_conversation_model),
+                    failure_cluster_threshold.unwrap_or(0.85),
                 ))
             } else {
                 None
             };
 
-            let tested_model = if let Some(mcp_manager) = mcp_manager {
-                Arc::new(TestedModel::with_mcp(
-                    Arc::clone(&conversation_model),
-                    mcp_manager,
-                ))
+            let exporters = exports
+                .iter()
+                .map(|target| create_exporter(target, &http))
+                .collect::<Result<Vec<_>>>()?;
+
+            let hooks = if let Some(hooks_content) = hooks_content {
+                let hooks_config: HooksConfig = serde_json::from_str(&hooks_content)?;
+                Hooks::new(hooks_config)
             } else {
-                Arc::new(TestedModel::new(Arc::clone(&conversation_model)))
+                Hooks::new(HooksConfig::default())
             };
 
-            let _judge_model_name =
-                judge_model.unwrap_or_else(|| "claude-3-5-sonnet-20241022".to_string());
-            let judge_conversation_model = create_model("anthropic")?;
-            let judge_prompt = JudgePrompt::default();
-            let judge = Arc::new(JudgeModel::new(judge_conversation_model, judge_prompt));
-
-            let config_arc = Arc::new(config.clone());
-
             let mut ui = ui::TerminalUI::new();
-            let total_cases = cases.len();
+            let total_cases = eval_run.cases().len();
             ui.print_header(&config, total_cases);
 
+            hooks
+                .dispatch(&HookEvent::PreRun {
+                    configuration: &config,
+                    total_cases,
+                })
+                .await?;
+
             ui.create_progress_bar(total_cases as u64);
 
-            let judge_for_report = Arc::clone(&judge);
-            let stream = run_eval_stream(cases, tested_model, config_arc, judge, threshold);
-            tokio::pin!(stream);
+            let mut stream = Box::pin(eval_run.stream());
             let mut results = Vec::new();
             let mut passed_count = 0;
             let mut failed_count = 0;
+            let mut run_error = None;
 
             while let Some(result) = stream.next().await {
                 ui.set_current_case(results.len() + 1, passed_count, failed_count);
@@ -324,37 +897,678 @@ async fn main() -> Result<()> {
                             passed_count,
                             failed_count,
                         );
+
+                        if let Err(e) = hooks
+                            .dispatch(&HookEvent::PostCase {
+                                result: &eval_result,
+                            })
+                            .await
+                        {
+                            run_error = Some(e);
+                            break;
+                        }
+
                         results.push(eval_result);
                     }
+                    Err(e) => {
+                        run_error = Some(e);
+                        break;
+                    }
+                }
+            }
+
+            ui.finish_progress();
+            drop(stream);
+            eval_run.finish_teardowns().await;
+            if let Some(e) = run_error {
+                eprintln!("  ✗ Error: {}", e);
+                return Err(e);
+            }
+            if let (Some(path), Some(CassetteMode::Record(_))) = (&manifest, &http.cassettes) {
+                run_manifest.hash_cassettes().await?;
+                run_manifest.save(path).await?;
+            }
+
+            let mut history = Vec::new();
+            for report_path in &difficulty_reports {
+                history.push(read_report(report_path).await?);
+            }
+            let estimator = DifficultyEstimator::from_reports(&history);
+            for result in &mut results {
+                result.difficulty = estimator.estimate(&result.case);
+            }
+
+            let execution_time = start_time.elapsed().as_secs_f64();
+            ui.print_summary(&results, threshold, execution_time);
+            if verbose {
+                ui.print_failure_details(&results);
+            }
+
+            let mut report = eval_run.report(&results, execution_time)?;
+            let author = std::env::var("USER").ok();
+            report.metadata.notes = notes
+                .into_iter()
+                .map(|text| Note::new(text, author.clone()))
+                .collect();
+            ui.print_breakdowns(&report.summary.breakdowns, &breakdown_keys);
+            if let Some(early_stopping) = &report.early_stopping {
+                ui.print_early_stopping(early_stopping);
+            }
+
+            if let Some(failure_analyzer) = &failure_analyzer {
+                let spinner = ui.create_spinner("Analyzing failures...");
+                report.failure_modes = failure_analyzer.analyze(&results).await?;
+                spinner.finish_and_clear();
+                ui.print_failure_modes(&report.failure_modes);
+            }
+
+            hooks
+                .dispatch(&HookEvent::PostRun { report: &report })
+                .await?;
+
+            if save_history {
+                let store =
+                    HistoryStore::new(history_dir.as_deref().unwrap_or(DEFAULT_HISTORY_DIR));
+                let path = store.save(&report).await?;
+                println!("  ✓ Run stored in {}", path.display());
+            }
+
+            for (target, exporter) in exports.iter().zip(&exporters) {
+                let spinner = ui.create_spinner(&format!("Exporting to {}...", target));
+                exporter.export(&report).await?;
+                spinner.finish_with_message(format!("Exported to {}", target));
+            }
+
+            if let Some(output_file) = output {
+                let spinner = ui.create_spinner("Generating report...");
+
+                let report_json = serde_json::to_string_pretty(&report)?;
+                write_output(&output_file, report_json.into_bytes(), &http).await?;
+
+                spinner.finish_with_message(format!("Report saved to {}", output_file));
+            }
+        }
+        Commands::Rejudge {
+            report,
+            threshold,
+            judge_model,
+            judge_prompt,
+            judge_prompt_version,
+            verbose,
+            output,
+        } => {
+            let start_time = std::time::Instant::now();
+            let previous = read_report(&report).await?;
+            let threshold = threshold.unwrap_or(previous.metadata.threshold);
+
+            let judge_prompt = match judge_prompt {
+                Some(path) => load_judge_prompt(&path).await?,
+                None => previous.judge_configuration.clone(),
+            };
+            let judge_model = judge_model
+                .or_else(|| previous.metadata.judge_model.clone())
+                .unwrap_or_else(|| JUDGE_MODEL.to_string());
+            let judge = Arc::new(
+                JudgeModel::new(
+                    create_model_with_http_config(Provider::Anthropic, &http)?,
+                    judge_prompt,
+                )
+                .with_model_name(judge_model)
+                .with_threshold(threshold),
+            );
+            let cases: Vec<EvalCase> = previous
+                .results
+                .iter()
+                .map(|result| EvalCase::from(&result.case))
+                .collect();
+            let scorers = Arc::new(Scorers::load(Arc::clone(&judge), None, &cases)?);
+
+            let mut ui = ui::TerminalUI::new();
+            let total_cases = previous.results.len();
+            ui.print_header(&previous.configuration, total_cases);
+            ui.create_progress_bar(total_cases as u64);
+
+            let stream = rejudge_stream(previous.results.clone(), scorers, threshold);
+            tokio::pin!(stream);
+            let mut results = Vec::with_capacity(total_cases);
+            let mut passed_count = 0;
+
+            while let Some(result) = stream.next().await {
+                let eval_result = match result {
+                    Ok(eval_result) => eval_result,
                     Err(e) => {
                         ui.finish_progress();
                         eprintln!("  ✗ Error: {}", e);
                         return Err(e);
                     }
+                };
+                if eval_result.passed {
+                    passed_count += 1;
                 }
+                results.push(eval_result);
+                ui.update_progress(
+                    results.len(),
+                    total_cases,
+                    passed_count,
+                    results.len() - passed_count,
+                );
             }
 
             ui.finish_progress();
 
-            ui.print_summary(&results, threshold, start_time.elapsed().as_secs_f64());
+            let execution_time = start_time.elapsed().as_secs_f64();
+            ui.print_summary(&results, threshold, execution_time);
+            if verbose {
+                ui.print_failure_details(&results);
+            }
+
+            let mut report = generate_report(
+                &results,
+                &previous.configuration,
+                judge.prompt(),
+                threshold,
+                execution_time,
+            )?;
+            report.metadata.suite = previous.metadata.suite;
+            report.metadata.system_prompt = previous.metadata.system_prompt;
+            report.metadata.judge_model = Some(judge.model_name().to_string());
+            report.metadata.notes = previous.metadata.notes;
+            let breakdown_keys: Vec<String> = previous.summary.breakdowns.into_keys().collect();
+            report.summary.breakdowns = metadata_breakdowns(&results, &breakdown_keys);
+            if let Some(judge_prompt) = &mut report.metadata.judge_prompt {
+                judge_prompt.version = match judge_prompt_version {
+                    Some(version) => Some(version),
+                    None => previous
+                        .metadata
+                        .judge_prompt
+                        .filter(|previous| previous.hash == judge_prompt.hash)
+                        .and_then(|previous| previous.version),
+                };
+            }
 
             if let Some(output_file) = output {
                 let spinner = ui.create_spinner("Generating report...");
 
-                let report = generate_report(
-                    &results,
-                    &config,
-                    &judge_for_report.prompt(),
-                    threshold,
-                    start_time.elapsed().as_secs_f64(),
-                )?;
-
                 let report_json = serde_json::to_string_pretty(&report)?;
-                tokio::fs::write(&output_file, report_json).await?;
+                write_output(&output_file, report_json.into_bytes(), &http).await?;
 
                 spinner.finish_with_message(format!("Report saved to {}", output_file));
             }
         }
+        Commands::Annotate {
+            report: report_path,
+            case_id,
+            note,
+            author,
+        } => {
+            let mut report = read_report(&report_path).await?;
+            report.annotate(case_id.as_deref(), Note::new(note, author))?;
+            report.schema_version = SCHEMA_VERSION;
+            tokio::fs::write(&report_path, serde_json::to_string_pretty(&report)?)
+                .await
+                .map_err(|e| anyhow!("Failed to write report '{}': {}", report_path, e))?;
+
+            match case_id {
+                Some(case_id) => println!("  ✓ Note added to case {} in {}", case_id, report_path),
+                None => println!("  ✓ Note added to {}", report_path),
+            }
+        }
+        Commands::Snapshot {
+            cases_file,
+            cases_headers,
+            model,
+            snapshots,
+            bless,
+            mode,
+            threshold,
+            mcp_servers,
+        } => {
+            let cases_content = read_cases_source(&cases_file, &cases_headers, &http).await?;
+            let mut suite_config = Suite::from_json(&cases_content)?;
+            suite_config.preprocess(&cases_file).await?;
+            let cases = suite_config.cases;
+            let mut config = model.into_config(None).await?;
+            config.session = suite_config.session;
+
+            let matcher = match mode.as_deref().unwrap_or("exact") {
+                "exact" => SnapshotMatcher::Exact,
+                "judge" => SnapshotMatcher::Judge {
+                    judge: Arc::new(JudgeModel::new(
+                        create_model_with_http_config(Provider::Anthropic, &http)?,
+                        JudgePrompt::default(),
+                    )),
+                    threshold: threshold.unwrap_or(0.8),
+                },
+                other => return Err(anyhow!("Unsupported snapshot mode: {}", other)),
+            };
+
+            let conversation_model = create_model_with_http_config(config.provider, &http)?;
+            let tested_model = Arc::new(if let Some(mcp_config_path) = mcp_servers {
+                let mcp_config_content = tokio::fs::read_to_string(&mcp_config_path).await?;
+                let mcp_config: McpServersConfig = serde_json::from_str(&mcp_config_content)?;
+                TestedModel::with_mcp(
+                    conversation_model,
+                    Arc::new(McpManager::start_servers(&mcp_config.servers).await?),
+                )
+            } else {
+                TestedModel::new(conversation_model)
+            });
+
+            let ui = ui::TerminalUI::new();
+            ui.print_header(&config, cases.len());
+
+            let spinner = ui.create_spinner("Generating outputs...");
+            let outputs = record_outputs(&cases, tested_model, Arc::new(config.clone())).await?;
+            spinner.finish_and_clear();
+
+            let existing = if bless {
+                None
+            } else {
+                match tokio::fs::read_to_string(&snapshots).await {
+                    Ok(content) => Some(serde_json::from_str::<SnapshotFile>(&content)?),
+                    Err(e) if e.kind() == std::io::ErrorKind::NotFound => None,
+                    Err(e) => return Err(e.into()),
+                }
+            };
+
+            match existing {
+                Some(blessed) => {
+                    let spinner = ui.create_spinner("Comparing with snapshots...");
+                    let comparisons = compare_snapshots(&blessed, &outputs, &matcher).await?;
+                    spinner.finish_and_clear();
+
+                    let changed = ui.print_snapshot_comparisons(&comparisons);
+                    if changed > 0 {
+                        return Err(anyhow!(
+                            "{} snapshots changed, rerun with --bless to accept them",
+                            changed
+                        ));
+                    }
+                }
+                None => {
+                    let snapshot_file = SnapshotFile {
+                        blessed_at: Utc::now(),
+                        configuration: config,
+                        snapshots: outputs,
+                    };
+                    tokio::fs::write(&snapshots, serde_json::to_string_pretty(&snapshot_file)?)
+                        .await?;
+                    println!(
+                        "  ✓ Blessed {} snapshots in {}",
+                        snapshot_file.snapshots.len(),
+                        snapshots
+                    );
+                }
+            }
+        }
+        Commands::Sweep {
+            cases_file,
+            model,
+            parameter,
+            values,
+            range,
+            threshold,
+            iterations,
+            mcp_servers,
+            allow_case_commands,
+            output,
+        } => {
+            let values = match range {
+                Some(range) => range_values(&range)?,
+                None => values,
+            };
+            let values = values
+                .iter()
+                .map(|value| parameter.parse_value(value))
+                .collect::<Result<Vec<_>>>()?;
+
+            let cases_content = read_cases_source(&cases_file, &[], &http).await?;
+            let mut suite_config = Suite::from_json(&cases_content)?;
+            suite_config.check_case_commands(&cases_file, allow_case_commands)?;
+            suite_config.preprocess(&cases_file).await?;
+            let mut base_config = model.into_config(iterations).await?;
+            base_config.session = suite_config.session.clone();
+            let judge_conversation_model =
+                create_model_with_http_config(Provider::Anthropic, &http)?;
+
+            let mcp_manager = match mcp_servers {
+                Some(mcp_config_path) => {
+                    let mcp_config_content = tokio::fs::read_to_string(&mcp_config_path).await?;
+                    let mcp_config: McpServersConfig = serde_json::from_str(&mcp_config_content)?;
+                    Some(Arc::new(
+                        McpManager::start_servers(&mcp_config.servers).await?,
+                    ))
+                }
+                None => None,
+            };
+
+            let ui = ui::TerminalUI::new();
+            ui.print_header(&base_config, suite_config.cases.len());
+
+            let mut points = Vec::with_capacity(values.len());
+            for value in values {
+                let config = parameter.apply(&base_config, &value)?;
+                let mut run_builder = EvalRun::builder(config.clone())
+                    .suite(suite_name(&cases_file))
+                    .cases(suite_config.cases.clone())
+                    .categories(suite_config.categories.clone())
+                    .threshold(threshold.unwrap_or(0.8))
+                    .model(create_model_with_http_config(config.provider, &http)?)
+                    .judge_model(Arc::clone(&judge_conversation_model));
+                if let Some(mcp_manager) = &mcp_manager {
+                    run_builder = run_builder.mcp_manager(Arc::clone(mcp_manager));
+                }
+
+                let spinner = ui.create_spinner(&format!("Running {}={}...", parameter, value));
+                let report = run_builder.build()?.execute().await?;
+                spinner.finish_and_clear();
+
+                points.push(SweepPoint::from_report(value, &report));
+            }
+
+            let sweep = Sweep { parameter, points };
+            ui.print_sweep(&sweep);
+
+            if let Some(output_file) = output {
+                write_output(&output_file, serde_json::to_vec_pretty(&sweep)?, &http).await?;
+            }
+        }
+        Commands::Compare {
+            baseline,
+            candidate,
+        } => {
+            let baseline = read_report(&baseline).await?;
+            let candidate = read_report(&candidate).await?;
+            let comparison = compare_reports(&baseline, &candidate);
+
+            let ui = ui::TerminalUI::new();
+            ui.print_comparison(&baseline, &candidate, &comparison);
+        }
+        Commands::RescoreHistory {
+            judge,
+            judge_prompt,
+            threshold,
+            suite,
+            history_dir,
+            output_dir,
+            output,
+        } => {
+            let store = HistoryStore::new(history_dir.as_deref().unwrap_or(DEFAULT_HISTORY_DIR));
+            let rescored_store = output_dir.map(HistoryStore::new);
+            let suites = match suite {
+                Some(suite) => vec![suite],
+                None => store.suites().await?,
+            };
+
+            let judge_prompt = match judge_prompt {
+                Some(path) => load_judge_prompt(&path).await?,
+                None => JudgePrompt::default(),
+            };
+            let mut rescorer = HistoryRescorer::new(
+                create_model_with_http_config(Provider::Anthropic, &http)?,
+                judge.clone(),
+                judge_prompt,
+            );
+            if let Some(threshold) = threshold {
+                rescorer = rescorer.with_threshold(threshold);
+            }
+
+            let ui = ui::TerminalUI::new();
+            let mut trends = Vec::with_capacity(suites.len());
+            for suite in &suites {
+                let reports = store.load_suite(suite).await?;
+                let mut rescored = Vec::with_capacity(reports.len());
+                for (index, report) in reports.iter().enumerate() {
+                    let spinner = ui.create_spinner(&format!(
+                        "Re-scoring {} run {}/{}...",
+                        suite,
+                        index + 1,
+                        reports.len()
+                    ));
+                    let report = rescorer.rescore(report).await?;
+                    spinner.finish_and_clear();
+                    if let Some(rescored_store) = &rescored_store {
+                        rescored_store.save(&report).await?;
+                    }
+                    rescored.push(report);
+                }
+
+                let trend = RescoredTrend::new(suite, &judge, &reports, &rescored);
+                ui.print_rescored_trend(&trend);
+                trends.push(trend);
+            }
+
+            if let Some(output_file) = output {
+                let trends_json = serde_json::to_string_pretty(&trends)?;
+                write_output(&output_file, trends_json.into_bytes(), &http).await?;
+                println!("  ✓ Trends saved to {}", output_file);
+            }
+        }
+        Commands::Leaderboard {
+            suite,
+            history_dir,
+            format,
+            output,
+            prices,
+        } => {
+            let store = HistoryStore::new(history_dir.as_deref().unwrap_or(DEFAULT_HISTORY_DIR));
+            let prices = match prices {
+                Some(path) => PriceTable::load(&path).await?,
+                None => PriceTable::default(),
+            };
+            let suites = match suite {
+                Some(suite) => vec![suite],
+                None => store.suites().await?,
+            };
+
+            let mut leaderboards = Vec::with_capacity(suites.len());
+            for suite in &suites {
+                let reports = store.load_suite(suite).await?;
+                leaderboards.push(Leaderboard::from_reports(suite, &reports, &prices));
+            }
+
+            let rendered = match format.as_deref().unwrap_or("terminal") {
+                "terminal" => {
+                    let ui = ui::TerminalUI::new();
+                    for leaderboard in &leaderboards {
+                        ui.print_leaderboard(leaderboard);
+                    }
+                    None
+                }
+                "markdown" => Some(
+                    leaderboards
+                        .iter()
+                        .map(Leaderboard::to_markdown)
+                        .collect::<Vec<_>>()
+                        .join("\n"),
+                ),
+                "json" => Some(serde_json::to_string_pretty(&leaderboards)?),
+                other => return Err(anyhow!("Unsupported leaderboard format: {}", other)),
+            };
+
+            if let Some(rendered) = rendered {
+                match output {
+                    Some(output_file) => tokio::fs::write(&output_file, rendered).await?,
+                    None => println!("{}", rendered),
+                }
+            }
+        }
+        Commands::Daemon { config } => {
+            let config_content = tokio::fs::read_to_string(&config)
+                .await
+                .map_err(|e| anyhow!("Failed to read daemon config '{}': {}", config, e))?;
+            let config: DaemonConfig = serde_json::from_str(&config_content)
+                .map_err(|e| anyhow!("Invalid daemon config '{}': {}", config, e))?;
+
+            let ui = ui::TerminalUI::new();
+            let mut events = Daemon::new(config, http)?.spawn();
+            while let Some(event) = events.recv().await {
+                ui.print_daemon_event(&event);
+            }
+        }
+        Commands::Server {
+            address,
+            token,
+            history_dir,
+            cases_dir,
+            allow_case_commands,
+            max_runs,
+        } => {
+            let address = address.unwrap_or_else(|| ([127, 0, 0, 1], 8080).into());
+            let policy = ServerPolicy {
+                cases_dir: cases_dir.map(Into::into),
+                allow_case_commands,
+                max_runs: max_runs.unwrap_or(ServerPolicy::default().max_runs),
+            };
+            println!("Listening on http://{}", address);
+            serve(
+                address,
+                http,
+                HistoryStore::new(history_dir.as_deref().unwrap_or(DEFAULT_HISTORY_DIR)),
+                token,
+                policy,
+            )
+            .await?;
+        }
+        Commands::Import {
+            format,
+            input,
+            output,
+        } => {
+            let content = tokio::fs::read_to_string(&input)
+                .await
+                .map_err(|e| anyhow!("Failed to read '{}': {}", input, e))?;
+            let cases = import_cases(&format, &content)?;
+            let cases_json = serde_json::to_string_pretty(&cases)?;
+
+            if let Some(output_file) = output {
+                tokio::fs::write(&output_file, cases_json).await?;
+                eprintln!("  ✓ Imported {} cases to {}", cases.len(), output_file);
+            } else {
+                println!("{}", cases_json);
+            }
+        }
+        Commands::Dedupe {
+            cases_file,
+            threshold,
+            embedding_provider,
+            embedding_model,
+            output,
+            report,
+        } => {
+            let cases_content = read_cases_source(&cases_file, &[], &http).await?;
+            let mut suite = Suite::from_json(&cases_content)?;
+            let cases = std::mem::take(&mut suite.cases);
+
+            let embedding_model = create_embedding_model_with_http_config(
+                embedding_provider.as_deref().unwrap_or("openai"),
+                embedding_model
+                    .as_deref()
+                    .unwrap_or("text-embedding-3-small"),
+                &http,
+            )?;
+
+            let ui = ui::TerminalUI::new();
+            let spinner = ui.create_spinner(&format!("Embedding {} cases...", cases.len()));
+            let (kept, dedupe_report) =
+                dedupe_cases(cases, embedding_model.as_ref(), threshold.unwrap_or(0.95)).await?;
+            spinner.finish_and_clear();
+
+            suite.cases = kept;
+            tokio::fs::write(&output, suite.to_json()?).await?;
+            println!(
+                "  ✓ kept {}/{} cases · {} duplicates removed · saved to {}",
+                dedupe_report.kept_count,
+                dedupe_report.original_count,
+                dedupe_report.removed_count,
+                output
+            );
+
+            if let Some(report_file) = report {
+                tokio::fs::write(&report_file, serde_json::to_string_pretty(&dedupe_report)?)
+                    .await?;
+            }
+        }
+        Commands::Suggest {
+            report,
+            system,
+            sample,
+            provider,
+            model,
+            output_prompt,
+        } => {
+            let report = read_report(&report).await?;
+
+            let system_prompt = match system {
+                Some(system_str) => load_system_prompt(system_str).await?,
+                None => report
+                    .configuration
+                    .system
+                    .as_ref()
+                    .map(SystemPrompt::text)
+                    .ok_or_else(|| {
+                        anyhow!("The report has no system prompt, pass one with --system")
+                    })?,
+            };
+
+            let defaults = ModelConfig::default();
+            let config = ModelConfig {
+                provider: provider.unwrap_or(defaults.provider),
+                model: model.unwrap_or(defaults.model.clone()),
+                max_tokens: 4000,
+                ..defaults
+            };
+            let suggester = PromptSuggester::new(
+                create_model_with_http_config(config.provider, &http)?,
+                config,
+            );
+
+            let ui = ui::TerminalUI::new();
+            let spinner = ui.create_spinner("Analyzing failures...");
+            let suggestions = suggester
+                .suggest(&system_prompt, &report, sample.unwrap_or(10))
+                .await?;
+            spinner.finish_and_clear();
+
+            ui.print_suggestions(&suggestions);
+
+            if let Some(output_file) = output_prompt {
+                tokio::fs::write(&output_file, &suggestions.revised_prompt).await?;
+                println!("  ✓ Candidate prompt saved to {}", output_file);
+            }
+        }
+        Commands::TuneThreshold {
+            report,
+            labels,
+            min,
+            max,
+            step,
+            output,
+        } => {
+            let report = read_report(&report).await?;
+
+            let labels: Option<Vec<HumanLabel>> = match labels {
+                Some(labels_path) => {
+                    let labels_content = tokio::fs::read_to_string(&labels_path).await?;
+                    Some(serde_json::from_str(&labels_content)?)
+                }
+                None => None,
+            };
+
+            let sweep = sweep_thresholds(
+                &report,
+                labels.as_deref(),
+                min.unwrap_or(0.0),
+                max.unwrap_or(1.0),
+                step.unwrap_or(0.05),
+            )?;
+
+            ui::TerminalUI::new().print_threshold_sweep(&sweep, report.metadata.threshold);
+
+            if let Some(output_file) = output {
+                tokio::fs::write(&output_file, serde_json::to_string_pretty(&sweep)?).await?;
+            }
+        }
     }
 
     Ok(())
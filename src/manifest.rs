@@ -0,0 +1,316 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use anyhow::{Result, anyhow};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::{
+    cases_source::{cache_key, is_remote},
+    conversation_model::anthropic,
+    early_stopping::EarlyStopping,
+    evaluation::{EvalCase, PromptVersion},
+    judge::{JUDGE_MODEL, JudgePrompt},
+    model_config::ModelConfig,
+    scorer::ScorerConfig,
+};
+
+/// Everything a run depends on, written by `evals run --manifest` so the run
+/// can be repeated with `evals run --from-manifest`.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct RunManifest {
+    pub created_at: DateTime<Utc>,
+    pub evals_version: String,
+    pub cases_file: String,
+    /// SHA-256 of the cases file as read.
+    pub cases_hash: String,
+    /// SHA-256 of the case inputs and expected outputs after templating, which
+    /// also covers included files and variables.
+    pub rendered_cases_hash: String,
+    /// Key of a remote cases file in the local cache.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cases_cache_key: Option<String>,
+    /// The configuration as resolved from the command line, including the
+    /// system prompt text and the suite's session.
+    pub configuration: ModelConfig,
+    /// The `--system` arguments, read again when the run is repeated.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub system_sources: Vec<String>,
+    pub threshold: f64,
+    pub judge_model: String,
+    pub judge_configuration: JudgePrompt,
+    /// The `--judge-prompt` file, read again when the run is repeated.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub judge_prompt_file: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub system_prompt: Option<PromptVersion>,
+    pub judge_prompt: PromptVersion,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mcp_servers: Option<RecordedFile>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub hooks: Option<RecordedFile>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub breakdown_by: Vec<String>,
+    pub anthropic_version: String,
+    /// Seed of the case order under early stopping.
+    pub seed: u64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub early_stopping: Option<EarlyStopping>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub scorer: Option<ScorerConfig>,
+    /// WASM scorer modules, of the run and of individual cases.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub scorer_modules: Vec<RecordedFile>,
+    /// Files named in case `setup` and `teardown` commands, such as the
+    /// scripts they run.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub case_scripts: Vec<RecordedFile>,
+    /// Cassettes directory; runs from the manifest replay from it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cassettes: Option<String>,
+    /// SHA-256 of the cassettes, once recorded.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cassettes_hash: Option<String>,
+}
+
+/// A configuration file the run read, with the SHA-256 of its content.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct RecordedFile {
+    pub path: String,
+    pub hash: String,
+}
+
+impl RecordedFile {
+    pub fn new(path: &str, content: impl AsRef<[u8]>) -> Self {
+        Self {
+            path: path.to_string(),
+            hash: format!("{:x}", Sha256::digest(content)),
+        }
+    }
+
+    pub async fn read(path: &str) -> Result<Self> {
+        let content = tokio::fs::read(path)
+            .await
+            .map_err(|e| anyhow!("Failed to read '{}': {}", path, e))?;
+        Ok(Self::new(path, content))
+    }
+}
+
+impl RunManifest {
+    pub fn new(
+        cases_file: &str,
+        cases_content: &str,
+        cases: &[EvalCase],
+        configuration: ModelConfig,
+        judge_configuration: JudgePrompt,
+        threshold: f64,
+    ) -> Self {
+        Self {
+            created_at: Utc::now(),
+            evals_version: env!("CARGO_PKG_VERSION").to_string(),
+            cases_file: cases_file.to_string(),
+            cases_hash: format!("{:x}", Sha256::digest(cases_content.as_bytes())),
+            rendered_cases_hash: rendered_cases_hash(cases),
+            cases_cache_key: is_remote(cases_file).then(|| cache_key(cases_file)),
+            system_prompt: configuration
+                .system
+                .as_ref()
+                .map(|system| PromptVersion::new(&system.text())),
+            judge_prompt: PromptVersion::new(&judge_configuration.text()),
+            configuration,
+            system_sources: Vec::new(),
+            threshold,
+            judge_model: JUDGE_MODEL.to_string(),
+            judge_configuration,
+            judge_prompt_file: None,
+            mcp_servers: None,
+            hooks: None,
+            breakdown_by: Vec::new(),
+            anthropic_version: anthropic::API_VERSION.to_string(),
+            seed: 0,
+            early_stopping: None,
+            scorer: None,
+            scorer_modules: Vec::new(),
+            case_scripts: Vec::new(),
+            cassettes: None,
+            cassettes_hash: None,
+        }
+    }
+
+    /// Hashes the files the run reads besides its configuration: the scorer
+    /// modules, the files named in case commands and the cassettes. Set
+    /// `scorer` and `cassettes` first.
+    pub async fn hash_files(&mut self, cases: &[EvalCase]) -> Result<()> {
+        let mut scorer_paths = BTreeSet::new();
+        for scorer in self
+            .scorer
+            .iter()
+            .chain(cases.iter().filter_map(|case| case.scorer.as_ref()))
+        {
+            let ScorerConfig::Wasm { path } = scorer;
+            scorer_paths.insert(path.as_str());
+        }
+        self.scorer_modules = Vec::with_capacity(scorer_paths.len());
+        for path in scorer_paths {
+            self.scorer_modules.push(RecordedFile::read(path).await?);
+        }
+
+        // Commands are shell strings, so any word naming a file counts.
+        let mut script_paths = BTreeSet::new();
+        for command in cases
+            .iter()
+            .flat_map(|case| case.setup.iter().chain(&case.teardown))
+        {
+            for word in command.command.split_whitespace() {
+                if tokio::fs::metadata(word)
+                    .await
+                    .is_ok_and(|metadata| metadata.is_file())
+                {
+                    script_paths.insert(word);
+                }
+            }
+        }
+        self.case_scripts = Vec::with_capacity(script_paths.len());
+        for path in script_paths {
+            self.case_scripts.push(RecordedFile::read(path).await?);
+        }
+
+        self.hash_cassettes().await
+    }
+
+    /// Hashes the files in the cassettes directory, if it exists; call it
+    /// again once a recording run has written them.
+    pub async fn hash_cassettes(&mut self) -> Result<()> {
+        let Some(dir) = &self.cassettes else {
+            return Ok(());
+        };
+        let mut entries = match tokio::fs::read_dir(dir).await {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+                self.cassettes_hash = None;
+                return Ok(());
+            }
+            Err(e) => return Err(anyhow!("Failed to read cassettes '{}': {}", dir, e)),
+        };
+
+        let mut files = BTreeMap::new();
+        while let Some(entry) = entries.next_entry().await? {
+            if entry.file_type().await?.is_file() {
+                files.insert(entry.file_name(), tokio::fs::read(entry.path()).await?);
+            }
+        }
+        let mut hasher = Sha256::new();
+        for (name, content) in files {
+            hasher.update(name.as_encoded_bytes());
+            hasher.update([0]);
+            hasher.update(Sha256::digest(content));
+        }
+        self.cassettes_hash = Some(format!("{:x}", hasher.finalize()));
+        Ok(())
+    }
+
+    pub async fn load(path: &str) -> Result<Self> {
+        let content = tokio::fs::read_to_string(path)
+            .await
+            .map_err(|e| anyhow!("Failed to read manifest '{}': {}", path, e))?;
+        serde_json::from_str(&content).map_err(|e| anyhow!("Invalid manifest '{}': {}", path, e))
+    }
+
+    pub async fn save(&self, path: &str) -> Result<()> {
+        tokio::fs::write(path, serde_json::to_string_pretty(self)?)
+            .await
+            .map_err(|e| anyhow!("Failed to write manifest '{}': {}", path, e))
+    }
+
+    /// Differences between the inputs recorded in this manifest and `current`,
+    /// as read again from disk. Settings copied from the manifest, such as the
+    /// judge model, cannot drift.
+    pub fn drift(&self, current: &RunManifest) -> Vec<String> {
+        let prompt_hash = |prompt: &Option<PromptVersion>| {
+            prompt
+                .as_ref()
+                .map_or_else(|| "none".to_string(), |prompt| prompt.hash.clone())
+        };
+        let file_hash = |file: &Option<RecordedFile>| {
+            file.as_ref()
+                .map_or_else(|| "none".to_string(), |file| file.hash.clone())
+        };
+        let files_hash = |files: &[RecordedFile]| {
+            if files.is_empty() {
+                return "none".to_string();
+            }
+            let mut hasher = Sha256::new();
+            for file in files {
+                hasher.update(file.path.as_bytes());
+                hasher.update([0]);
+                hasher.update(file.hash.as_bytes());
+            }
+            format!("{:x}", hasher.finalize())
+        };
+        let cassettes_hash = |hash: &Option<String>| hash.clone().unwrap_or("none".to_string());
+        let checks = [
+            ("evals version", &self.evals_version, &current.evals_version),
+            ("cases file", &self.cases_hash, &current.cases_hash),
+            (
+                "rendered cases",
+                &self.rendered_cases_hash,
+                &current.rendered_cases_hash,
+            ),
+            (
+                "system prompt",
+                &prompt_hash(&self.system_prompt),
+                &prompt_hash(&current.system_prompt),
+            ),
+            (
+                "judge prompt",
+                &self.judge_prompt.hash,
+                &current.judge_prompt.hash,
+            ),
+            (
+                "MCP servers",
+                &file_hash(&self.mcp_servers),
+                &file_hash(&current.mcp_servers),
+            ),
+            ("hooks", &file_hash(&self.hooks), &file_hash(&current.hooks)),
+            (
+                "scorer modules",
+                &files_hash(&self.scorer_modules),
+                &files_hash(&current.scorer_modules),
+            ),
+            (
+                "case scripts",
+                &files_hash(&self.case_scripts),
+                &files_hash(&current.case_scripts),
+            ),
+            (
+                "cassettes",
+                &cassettes_hash(&self.cassettes_hash),
+                &cassettes_hash(&current.cassettes_hash),
+            ),
+            (
+                "anthropic version",
+                &self.anthropic_version,
+                &current.anthropic_version,
+            ),
+        ];
+
+        checks
+            .into_iter()
+            .filter(|(_, recorded, current)| recorded != current)
+            .map(|(input, recorded, current)| format!("{}: {} → {}", input, recorded, current))
+            .collect()
+    }
+}
+
+fn rendered_cases_hash(cases: &[EvalCase]) -> String {
+    let mut hasher = Sha256::new();
+    for case in cases {
+        hasher.update(case.input.as_bytes());
+        hasher.update([0]);
+        if let Some(expected_output) = &case.expected_output {
+            hasher.update(serde_json::to_string(expected_output).unwrap_or_default());
+        }
+        hasher.update([0]);
+    }
+    format!("{:x}", hasher.finalize())
+}
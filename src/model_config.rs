@@ -1,32 +1,293 @@
+use std::{fmt, str::FromStr};
+
+use anyhow::{Result, anyhow};
 use serde::{Deserialize, Serialize};
 
-use crate::conversation_model::ToolDefinition;
+use crate::{conversation_model::ToolDefinition, session::SessionState};
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Hash)]
+#[serde(rename_all = "lowercase")]
+pub enum Provider {
+    Anthropic,
+    OpenAI,
+    /// OpenAI's Responses API, which also runs built-in tools such as web search.
+    #[serde(rename = "openai-responses")]
+    OpenAIResponses,
+}
+
+impl fmt::Display for Provider {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Provider::Anthropic => write!(f, "anthropic"),
+            Provider::OpenAI => write!(f, "openai"),
+            Provider::OpenAIResponses => write!(f, "openai-responses"),
+        }
+    }
+}
+
+impl FromStr for Provider {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "anthropic" => Ok(Provider::Anthropic),
+            "openai" => Ok(Provider::OpenAI),
+            "openai-responses" => Ok(Provider::OpenAIResponses),
+            _ => Err(anyhow::anyhow!("Unsupported provider: {}", s)),
+        }
+    }
+}
+
+/// A system prompt given as one text, or as blocks composed from several
+/// sources.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(untagged)]
+pub enum SystemPrompt {
+    Text(String),
+    Blocks(Vec<SystemBlock>),
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct SystemBlock {
+    pub text: String,
+    /// Provider-specific fields sent with the block, e.g. Anthropic's
+    /// `cache_control`. Providers without structured system content ignore them.
+    #[serde(flatten)]
+    pub extra: serde_json::Map<String, serde_json::Value>,
+}
+
+impl SystemBlock {
+    pub fn new(text: impl Into<String>) -> Self {
+        Self {
+            text: text.into(),
+            extra: serde_json::Map::new(),
+        }
+    }
+}
+
+impl SystemPrompt {
+    /// The prompt as a single text, blocks separated by blank lines, for
+    /// hashing, diffing and providers that take one string.
+    pub fn text(&self) -> String {
+        match self {
+            SystemPrompt::Text(text) => text.clone(),
+            SystemPrompt::Blocks(blocks) => blocks
+                .iter()
+                .map(|block| block.text.as_str())
+                .collect::<Vec<_>>()
+                .join("\n\n"),
+        }
+    }
+
+    /// The prompt as blocks; a text prompt is a single block.
+    pub fn blocks(&self) -> Vec<SystemBlock> {
+        match self {
+            SystemPrompt::Text(text) => vec![SystemBlock::new(text.clone())],
+            SystemPrompt::Blocks(blocks) => blocks.clone(),
+        }
+    }
+}
+
+impl From<String> for SystemPrompt {
+    fn from(text: String) -> Self {
+        SystemPrompt::Text(text)
+    }
+}
+
+impl From<&str> for SystemPrompt {
+    fn from(text: &str) -> Self {
+        SystemPrompt::Text(text.to_string())
+    }
+}
+
+impl From<Vec<SystemBlock>> for SystemPrompt {
+    fn from(blocks: Vec<SystemBlock>) -> Self {
+        SystemPrompt::Blocks(blocks)
+    }
+}
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct ModelConfig {
-    pub provider: String,
+    pub provider: Provider,
     pub model: String,
     pub max_tokens: u32,
+    /// Retry outputs cut off by `max_tokens` with double the limit, up to this cap.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_tokens_cap: Option<u32>,
     pub temperature: Option<f64>,
     pub top_k: Option<u32>,
     pub top_p: Option<f64>,
-    pub system: Option<String>,
+    pub system: Option<SystemPrompt>,
     pub tools: Option<Vec<ToolDefinition>>,
+    /// Tools the provider runs itself, e.g. `{"type": "web_search"}`; supported
+    /// by the OpenAI Responses API only.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub builtin_tools: Vec<serde_json::Value>,
     pub iterations: Option<usize>,
+    /// Session state injected into every request's context.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub session: Option<SessionState>,
+    /// Number of most likely alternatives to capture with each token's log
+    /// probability, at most [`MAX_TOP_LOGPROBS`]; supported by OpenAI only.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub logprobs: Option<u32>,
+    /// Provider-specific request fields (e.g. `response_format`, `service_tier`),
+    /// merged into the top level of the request body, overriding generated fields.
+    #[serde(default, skip_serializing_if = "serde_json::Value::is_null")]
+    pub extra_params: serde_json::Value,
+}
+
+/// OpenAI's limit on the alternatives returned per token.
+pub const MAX_TOP_LOGPROBS: u32 = 20;
+
+impl ModelConfig {
+    pub fn builder() -> ModelConfigBuilder {
+        ModelConfigBuilder {
+            config: ModelConfig::default(),
+        }
+    }
+
+    /// Rejects settings the provider would ignore or refuse.
+    pub fn validate(&self) -> Result<()> {
+        if self.max_tokens == 0 {
+            return Err(anyhow!("max_tokens must be at least 1"));
+        }
+        if let Some(cap) = self.max_tokens_cap
+            && cap <= self.max_tokens
+        {
+            return Err(anyhow!(
+                "max_tokens_cap ({}) must be greater than max_tokens ({})",
+                cap,
+                self.max_tokens
+            ));
+        }
+
+        if let Some(logprobs) = self.logprobs {
+            if self.provider == Provider::Anthropic {
+                return Err(anyhow!(
+                    "Log probabilities are not supported by {}",
+                    self.provider
+                ));
+            }
+            if logprobs > MAX_TOP_LOGPROBS {
+                return Err(anyhow!(
+                    "Log probabilities support at most {} alternatives, got {}",
+                    MAX_TOP_LOGPROBS,
+                    logprobs
+                ));
+            }
+        }
+
+        if !self.builtin_tools.is_empty() && self.provider != Provider::OpenAIResponses {
+            return Err(anyhow!(
+                "Built-in tools are only supported by {}, not {}",
+                Provider::OpenAIResponses,
+                self.provider
+            ));
+        }
+
+        Ok(())
+    }
 }
 
 impl Default for ModelConfig {
     fn default() -> Self {
         Self {
-            provider: "anthropic".to_string(),
+            provider: Provider::Anthropic,
             model: "claude-3-5-sonnet-20241022".to_string(),
             max_tokens: 1000,
+            max_tokens_cap: None,
             temperature: None,
             top_k: None,
             top_p: None,
             system: None,
             tools: None,
+            builtin_tools: Vec::new(),
             iterations: None,
+            session: None,
+            logprobs: None,
+            extra_params: serde_json::Value::Null,
         }
     }
 }
+
+/// Builds a [`ModelConfig`] starting from its defaults.
+pub struct ModelConfigBuilder {
+    config: ModelConfig,
+}
+
+impl ModelConfigBuilder {
+    pub fn provider(mut self, provider: Provider) -> Self {
+        self.config.provider = provider;
+        self
+    }
+
+    pub fn model(mut self, model: impl Into<String>) -> Self {
+        self.config.model = model.into();
+        self
+    }
+
+    pub fn max_tokens(mut self, max_tokens: u32) -> Self {
+        self.config.max_tokens = max_tokens;
+        self
+    }
+
+    pub fn max_tokens_cap(mut self, max_tokens_cap: u32) -> Self {
+        self.config.max_tokens_cap = Some(max_tokens_cap);
+        self
+    }
+
+    pub fn temperature(mut self, temperature: f64) -> Self {
+        self.config.temperature = Some(temperature);
+        self
+    }
+
+    pub fn top_k(mut self, top_k: u32) -> Self {
+        self.config.top_k = Some(top_k);
+        self
+    }
+
+    pub fn top_p(mut self, top_p: f64) -> Self {
+        self.config.top_p = Some(top_p);
+        self
+    }
+
+    pub fn system(mut self, system: impl Into<SystemPrompt>) -> Self {
+        self.config.system = Some(system.into());
+        self
+    }
+
+    pub fn tools(mut self, tools: Vec<ToolDefinition>) -> Self {
+        self.config.tools = Some(tools);
+        self
+    }
+
+    pub fn builtin_tools(mut self, builtin_tools: Vec<serde_json::Value>) -> Self {
+        self.config.builtin_tools = builtin_tools;
+        self
+    }
+
+    pub fn iterations(mut self, iterations: usize) -> Self {
+        self.config.iterations = Some(iterations);
+        self
+    }
+
+    pub fn session(mut self, session: SessionState) -> Self {
+        self.config.session = Some(session);
+        self
+    }
+
+    pub fn logprobs(mut self, top_logprobs: u32) -> Self {
+        self.config.logprobs = Some(top_logprobs);
+        self
+    }
+
+    pub fn extra_params(mut self, extra_params: serde_json::Value) -> Self {
+        self.config.extra_params = extra_params;
+        self
+    }
+
+    pub fn build(self) -> ModelConfig {
+        self.config
+    }
+}
@@ -0,0 +1,43 @@
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+/// Placeholders the providers return when a response has no usable content.
+const PROVIDER_ERROR_OUTPUTS: &[&str] = &["Failed to get response"];
+
+/// Why a generated output was rejected before judging.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum GenerationFailure {
+    Empty,
+    ProviderError,
+    EchoedInput,
+}
+
+impl fmt::Display for GenerationFailure {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GenerationFailure::Empty => write!(f, "Generation failure: empty output"),
+            GenerationFailure::ProviderError => {
+                write!(f, "Generation failure: provider returned no content")
+            }
+            GenerationFailure::EchoedInput => {
+                write!(f, "Generation failure: output repeats the input")
+            }
+        }
+    }
+}
+
+/// Detects outputs not worth spending judge tokens on.
+pub fn check_output(input: &str, output: &str) -> Option<GenerationFailure> {
+    let output = output.trim();
+    if output.is_empty() {
+        Some(GenerationFailure::Empty)
+    } else if PROVIDER_ERROR_OUTPUTS.contains(&output) {
+        Some(GenerationFailure::ProviderError)
+    } else if output == input.trim() {
+        Some(GenerationFailure::EchoedInput)
+    } else {
+        None
+    }
+}
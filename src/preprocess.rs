@@ -0,0 +1,154 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    path::PathBuf,
+};
+
+use anyhow::{Result, anyhow};
+
+/// Expands `{{ ... }}` tags in case text before it is sent:
+///
+/// - `{{name}}` inserts the suite variable `name`
+/// - `{{include "context.md"}}` inserts a file, relative to the cases file
+/// - `| trim`, `| upper` and `| lower` after either transform the inserted text,
+///   e.g. `{{include "context.md" | trim}}`
+///
+/// `\{{` inserts a literal `{{`. Included files are inserted as they are,
+/// without expanding their own tags.
+pub struct Preprocessor {
+    variables: BTreeMap<String, String>,
+    base_dir: PathBuf,
+    include_root: IncludeRoot,
+    includes: HashMap<PathBuf, String>,
+}
+
+enum IncludeRoot {
+    Any,
+    Within(PathBuf),
+    Disabled,
+}
+
+impl Preprocessor {
+    pub fn new(variables: BTreeMap<String, String>, base_dir: impl Into<PathBuf>) -> Self {
+        Self {
+            variables,
+            base_dir: base_dir.into(),
+            include_root: IncludeRoot::Any,
+            includes: HashMap::new(),
+        }
+    }
+
+    /// Refuses included files outside `root`, including through `..` or
+    /// symlinks.
+    pub fn confine_to(mut self, root: impl Into<PathBuf>) -> Self {
+        self.include_root = IncludeRoot::Within(root.into());
+        self
+    }
+
+    /// Refuses every `include`.
+    pub fn without_includes(mut self) -> Self {
+        self.include_root = IncludeRoot::Disabled;
+        self
+    }
+
+    pub async fn render(&mut self, template: &str) -> Result<String> {
+        let mut output = String::with_capacity(template.len());
+        let mut rest = template;
+
+        while let Some(start) = rest.find("{{") {
+            if let Some(before) = rest[..start].strip_suffix('\\') {
+                output.push_str(before);
+                output.push_str("{{");
+                rest = &rest[start + 2..];
+                continue;
+            }
+            output.push_str(&rest[..start]);
+            let after_open = &rest[start + 2..];
+            let end = after_open
+                .find("}}")
+                .ok_or_else(|| anyhow!("Unclosed '{{{{' in '{}'", template))?;
+            output.push_str(&self.expand(after_open[..end].trim()).await?);
+            rest = &after_open[end + 2..];
+        }
+        output.push_str(rest);
+
+        Ok(output)
+    }
+
+    async fn expand(&mut self, tag: &str) -> Result<String> {
+        let (value, filters) = match tag.strip_prefix("include") {
+            Some(arguments) if arguments.starts_with(char::is_whitespace) => {
+                let (path, filters) = parse_quoted(arguments.trim_start())
+                    .ok_or_else(|| anyhow!("Expected a quoted path in '{{{{{}}}}}'", tag))?;
+                (self.include(path).await?, filters)
+            }
+            _ => {
+                let (name, filters) = tag.split_at(tag.find('|').unwrap_or(tag.len()));
+                let name = name.trim();
+                let value = self.variables.get(name).cloned().ok_or_else(|| {
+                    anyhow!(
+                        "Unknown variable '{}'; define it in the suite's \"variables\"",
+                        name
+                    )
+                })?;
+                (value, filters)
+            }
+        };
+
+        let filters = filters.trim();
+        let filters = match filters.strip_prefix('|') {
+            Some(filters) => filters,
+            None if filters.is_empty() => return Ok(value),
+            None => return Err(anyhow!("Unexpected '{}' in '{{{{{}}}}}'", filters, tag)),
+        };
+        filters
+            .split('|')
+            .try_fold(value, |value, filter| match filter.trim() {
+                "trim" => Ok(value.trim().to_string()),
+                "upper" => Ok(value.to_uppercase()),
+                "lower" => Ok(value.to_lowercase()),
+                other => Err(anyhow!("Unknown filter '{}' in '{{{{{}}}}}'", other, tag)),
+            })
+    }
+
+    async fn include(&mut self, path: &str) -> Result<String> {
+        let path = self.base_dir.join(path);
+        if let Some(content) = self.includes.get(&path) {
+            return Ok(content.clone());
+        }
+
+        match &self.include_root {
+            IncludeRoot::Any => {}
+            IncludeRoot::Within(root) => {
+                let resolved = tokio::fs::canonicalize(&path)
+                    .await
+                    .map_err(|e| anyhow!("Failed to include '{}': {}", path.display(), e))?;
+                let root = tokio::fs::canonicalize(root)
+                    .await
+                    .map_err(|e| anyhow!("Failed to resolve '{}': {}", root.display(), e))?;
+                if !resolved.starts_with(&root) {
+                    return Err(anyhow!(
+                        "Refusing to include '{}' from outside '{}'",
+                        path.display(),
+                        root.display()
+                    ));
+                }
+            }
+            IncludeRoot::Disabled => {
+                return Err(anyhow!("Refusing to include '{}'", path.display()));
+            }
+        }
+
+        let content = tokio::fs::read_to_string(&path)
+            .await
+            .map_err(|e| anyhow!("Failed to include '{}': {}", path.display(), e))?;
+        self.includes.insert(path, content.clone());
+        Ok(content)
+    }
+}
+
+/// Splits `"quoted" rest` into the unquoted text and the rest.
+fn parse_quoted(text: &str) -> Option<(&str, &str)> {
+    let text = text.strip_prefix('"')?;
+    let end = text.find('"')?;
+    Some((&text[..end], &text[end + 1..]))
+}
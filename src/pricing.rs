@@ -0,0 +1,97 @@
+use std::collections::BTreeMap;
+
+use anyhow::{Result, anyhow};
+use serde::{Deserialize, Serialize};
+
+use crate::{conversation_model::TokenUsage, evaluation::EvaluationReport};
+
+/// Price of a model in USD per million tokens.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+pub struct ModelPrice {
+    pub input: f64,
+    pub output: f64,
+}
+
+impl ModelPrice {
+    pub fn cost(&self, usage: &TokenUsage) -> f64 {
+        (usage.input_tokens as f64 * self.input + usage.output_tokens as f64 * self.output)
+            / 1_000_000.0
+    }
+}
+
+/// Model prices keyed by model name prefix, so `claude-sonnet-4` also prices
+/// `claude-sonnet-4-20250514`; the longest matching prefix wins.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct PriceTable(BTreeMap<String, ModelPrice>);
+
+impl Default for PriceTable {
+    fn default() -> Self {
+        let prices = [
+            ("claude-opus-4-5", 5.0, 25.0),
+            ("claude-opus-4", 15.0, 75.0),
+            ("claude-sonnet-4", 3.0, 15.0),
+            ("claude-haiku-4", 1.0, 5.0),
+            ("claude-3-opus", 15.0, 75.0),
+            ("claude-3-7-sonnet", 3.0, 15.0),
+            ("claude-3-5-sonnet", 3.0, 15.0),
+            ("claude-3-5-haiku", 0.8, 4.0),
+            ("claude-3-haiku", 0.25, 1.25),
+            ("gpt-4.1", 2.0, 8.0),
+            ("gpt-4.1-mini", 0.4, 1.6),
+            ("gpt-4.1-nano", 0.1, 0.4),
+            ("gpt-4o", 2.5, 10.0),
+            ("gpt-4o-mini", 0.15, 0.6),
+            ("o3", 2.0, 8.0),
+            ("o3-mini", 1.1, 4.4),
+            ("o4-mini", 1.1, 4.4),
+        ];
+
+        Self(
+            prices
+                .into_iter()
+                .map(|(model, input, output)| (model.to_string(), ModelPrice { input, output }))
+                .collect(),
+        )
+    }
+}
+
+impl PriceTable {
+    /// The built-in prices, overridden and extended by a JSON file of
+    /// `{"model-prefix": {"input": 3.0, "output": 15.0}}`.
+    pub async fn load(path: &str) -> Result<Self> {
+        let content = tokio::fs::read_to_string(path)
+            .await
+            .map_err(|e| anyhow!("Failed to read prices '{}': {}", path, e))?;
+        let overrides: BTreeMap<String, ModelPrice> = serde_json::from_str(&content)
+            .map_err(|e| anyhow!("Invalid prices '{}': {}", path, e))?;
+
+        let mut table = Self::default();
+        table.0.extend(overrides);
+        Ok(table)
+    }
+
+    pub fn price(&self, model: &str) -> Option<ModelPrice> {
+        self.0
+            .iter()
+            .filter(|(prefix, _)| model.starts_with(prefix.as_str()))
+            .max_by_key(|(prefix, _)| prefix.len())
+            .map(|(_, price)| *price)
+    }
+
+    /// Cost of every generation in `report` with recorded token usage, or
+    /// `None` when the model has no price or no usage was recorded.
+    pub fn report_cost(&self, report: &EvaluationReport) -> Option<f64> {
+        let price = self.price(&report.configuration.model)?;
+        let usages: Vec<&TokenUsage> = report
+            .results
+            .iter()
+            .flat_map(|result| result.iterations.iter())
+            .filter_map(|iteration| iteration.generation.usage.as_ref())
+            .collect();
+        if usages.is_empty() {
+            return None;
+        }
+
+        Some(usages.into_iter().map(|usage| price.cost(usage)).sum())
+    }
+}
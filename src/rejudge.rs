@@ -0,0 +1,64 @@
+use std::sync::Arc;
+
+use anyhow::Result;
+use futures::stream::FuturesUnordered;
+use tokio_stream::Stream;
+
+use crate::{
+    evaluation::{EvalCase, EvalResult, PassAtKStats},
+    output_guard::check_output,
+    scorer::{Score, Scorers},
+};
+
+/// Re-scores the stored outputs of `results` without generating again,
+/// yielding results as they complete.
+pub fn rejudge_stream(
+    results: Vec<EvalResult>,
+    scorers: Arc<Scorers>,
+    threshold: f64,
+) -> impl Stream<Item = Result<EvalResult>> {
+    results
+        .into_iter()
+        .map(|mut result| {
+            let case = EvalCase::from(&result.case);
+            let scorer = scorers.for_case(&case);
+
+            async move {
+                let mut passed_count = 0;
+                let mut total_score = 0.0;
+
+                for iteration in &mut result.iterations {
+                    iteration.generation_failure =
+                        check_output(&case.input, &iteration.actual_output);
+                    let score = match iteration.generation_failure {
+                        Some(failure) => Score::new(0.0, failure.to_string()),
+                        None => scorer.score(&case, &iteration.actual_output).await?,
+                    };
+                    iteration.judge_score = score.score;
+                    iteration.judge_reasoning = score.reasoning;
+                    iteration.judge_output = score.details;
+                    iteration.passed =
+                        iteration.generation_failure.is_none() && score.score >= threshold;
+                    iteration.failure_category =
+                        score.failure_category.filter(|_| !iteration.passed);
+
+                    if iteration.passed {
+                        passed_count += 1;
+                    }
+                    total_score += score.score;
+                }
+
+                let iterations_count = result.iterations.len();
+                result.judge_score = total_score / iterations_count.max(1) as f64;
+                result.passed = passed_count > 0;
+                result.pass_at_k = (iterations_count > 1).then(|| PassAtKStats {
+                    total_iterations: iterations_count,
+                    passed_iterations: passed_count,
+                    pass_rate: passed_count as f64 / iterations_count as f64,
+                });
+
+                Ok(result)
+            }
+        })
+        .collect::<FuturesUnordered<_>>()
+}
@@ -0,0 +1,59 @@
+use std::sync::Arc;
+
+use anyhow::{Result, anyhow};
+use object_store::{
+    ObjectStore, ObjectStoreExt, aws::AmazonS3Builder, gcp::GoogleCloudStorageBuilder, path::Path,
+};
+
+use crate::transport::HttpConfig;
+
+/// Writes `contents` to a local path, or to object storage for `s3://` and
+/// `gs://` destinations using credentials from the environment and the proxy
+/// and certificates of `http`.
+pub async fn write_output(destination: &str, contents: Vec<u8>, http: &HttpConfig) -> Result<()> {
+    let client_options = http.client_options()?;
+    let store: Arc<dyn ObjectStore> = if destination.starts_with("s3://") {
+        let mut builder = AmazonS3Builder::from_env().with_url(destination);
+        if let Some(client_options) = client_options {
+            builder = builder.with_client_options(client_options);
+        }
+        Arc::new(
+            builder
+                .build()
+                .map_err(|e| anyhow!("Failed to configure S3 for '{}': {}", destination, e))?,
+        )
+    } else if destination.starts_with("gs://") {
+        let mut builder = GoogleCloudStorageBuilder::from_env().with_url(destination);
+        if let Some(client_options) = client_options {
+            builder = builder.with_client_options(client_options);
+        }
+        Arc::new(
+            builder
+                .build()
+                .map_err(|e| anyhow!("Failed to configure GCS for '{}': {}", destination, e))?,
+        )
+    } else {
+        tokio::fs::write(destination, contents).await?;
+        return Ok(());
+    };
+
+    let object_path = object_path(destination)?;
+    store
+        .put(&object_path, contents.into())
+        .await
+        .map_err(|e| anyhow!("Failed to upload '{}': {}", destination, e))?;
+
+    Ok(())
+}
+
+fn object_path(destination: &str) -> Result<Path> {
+    let (_, location) = destination
+        .split_once("://")
+        .ok_or_else(|| anyhow!("Invalid object storage URL '{}'", destination))?;
+    let (_, key) = location
+        .split_once('/')
+        .filter(|(_, key)| !key.is_empty())
+        .ok_or_else(|| anyhow!("Missing object key in '{}'", destination))?;
+
+    Path::parse(key).map_err(|e| anyhow!("Invalid object key in '{}': {}", destination, e))
+}
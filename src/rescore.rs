@@ -0,0 +1,141 @@
+use std::sync::Arc;
+
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use tokio_stream::StreamExt;
+
+use crate::{
+    conversation_model::ConversationModel,
+    evaluation::{EvalCase, EvaluationReport},
+    judge::{JudgeModel, JudgePrompt},
+    model_config::Provider,
+    rejudge::rejudge_stream,
+    runner::{generate_report, metadata_breakdowns},
+    scorer::Scorers,
+};
+
+/// Re-judges stored runs with one judge model and prompt, so runs judged
+/// by different judges over time can be compared.
+pub struct HistoryRescorer {
+    model: Arc<dyn ConversationModel>,
+    model_name: String,
+    prompt: JudgePrompt,
+    threshold: Option<f64>,
+}
+
+impl HistoryRescorer {
+    pub fn new(
+        model: Arc<dyn ConversationModel>,
+        model_name: impl Into<String>,
+        prompt: JudgePrompt,
+    ) -> Self {
+        Self {
+            model,
+            model_name: model_name.into(),
+            prompt,
+            threshold: None,
+        }
+    }
+
+    /// Applies one pass threshold to every run instead of each run's own.
+    pub fn with_threshold(mut self, threshold: f64) -> Self {
+        self.threshold = Some(threshold);
+        self
+    }
+
+    /// Re-judges the stored outputs of `report`, keeping its run time, suite
+    /// and notes.
+    pub async fn rescore(&self, report: &EvaluationReport) -> Result<EvaluationReport> {
+        let start_time = std::time::Instant::now();
+        let threshold = self.threshold.unwrap_or(report.metadata.threshold);
+        let judge = Arc::new(
+            JudgeModel::new(Arc::clone(&self.model), self.prompt.clone())
+                .with_model_name(self.model_name.clone())
+                .with_threshold(threshold),
+        );
+        let cases: Vec<EvalCase> = report
+            .results
+            .iter()
+            .map(|result| EvalCase::from(&result.case))
+            .collect();
+        let scorers = Arc::new(Scorers::load(Arc::clone(&judge), None, &cases)?);
+
+        let stream = rejudge_stream(report.results.clone(), scorers, threshold);
+        tokio::pin!(stream);
+        let mut results = Vec::with_capacity(report.results.len());
+        while let Some(result) = stream.next().await {
+            results.push(result?);
+        }
+
+        let mut rescored = generate_report(
+            &results,
+            &report.configuration,
+            judge.prompt(),
+            threshold,
+            start_time.elapsed().as_secs_f64(),
+        )?;
+        rescored.metadata.generated_at = report.metadata.generated_at;
+        rescored.metadata.suite = report.metadata.suite.clone();
+        rescored.metadata.system_prompt = report.metadata.system_prompt.clone();
+        rescored.metadata.judge_model = Some(self.model_name.clone());
+        rescored.metadata.notes = report.metadata.notes.clone();
+        let breakdown_keys: Vec<String> = report.summary.breakdowns.keys().cloned().collect();
+        rescored.summary.breakdowns = metadata_breakdowns(&results, &breakdown_keys);
+
+        Ok(rescored)
+    }
+}
+
+/// A suite's runs, oldest first, as originally scored and as re-scored.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct RescoredTrend {
+    pub suite: String,
+    pub judge_model: String,
+    pub points: Vec<TrendPoint>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct TrendPoint {
+    pub generated_at: DateTime<Utc>,
+    pub provider: Provider,
+    pub model: String,
+    /// The judge that originally scored the run, when recorded.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub original_judge_model: Option<String>,
+    pub original_pass_rate_percent: f64,
+    pub original_average_score: f64,
+    pub pass_rate_percent: f64,
+    pub average_score: f64,
+}
+
+impl RescoredTrend {
+    /// Pairs each original report with its re-scored counterpart.
+    pub fn new(
+        suite: &str,
+        judge_model: &str,
+        original: &[EvaluationReport],
+        rescored: &[EvaluationReport],
+    ) -> Self {
+        let points = original
+            .iter()
+            .zip(rescored)
+            .map(|(original, rescored)| TrendPoint {
+                generated_at: original.metadata.generated_at,
+                provider: original.configuration.provider,
+                model: original.configuration.model.clone(),
+                original_judge_model: original.metadata.judge_model.clone(),
+                original_pass_rate_percent: original.summary.pass_rate_percent,
+                original_average_score: original.summary.average_score,
+                pass_rate_percent: rescored.summary.pass_rate_percent,
+                average_score: rescored.summary.average_score,
+            })
+            .collect();
+
+        Self {
+            suite: suite.to_string(),
+            judge_model: judge_model.to_string(),
+            points,
+        }
+    }
+}
@@ -0,0 +1,684 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    sync::{Arc, Mutex as SyncMutex},
+    time::Duration,
+};
+
+use anyhow::{Result, anyhow};
+use chrono::Utc;
+use futures::{Stream, StreamExt, future::ready, stream};
+use rand::{SeedableRng, rngs::StdRng, seq::SliceRandom};
+use tokio::{sync::Mutex, task::JoinHandle};
+
+use crate::{
+    conversation_model::{ConversationModel, StopReason, create_model_with_http_config},
+    early_stopping::{EarlyStopping, EarlyStoppingReport},
+    evaluation::{
+        CategoryStats, EvalCase, EvalCaseReport, EvalResult, EvaluationReport, IterationResult,
+        PassAtKStats, PromptVersion, ReportMetadata, ReportSummary,
+    },
+    hooks::{HookCommand, run_shell_command},
+    judge::{JudgeModel, JudgePrompt, failure_category_counts},
+    mcp_manager::McpManager,
+    model_config::{ModelConfig, Provider},
+    output_guard::check_output,
+    schema::SCHEMA_VERSION,
+    scorer::{Score, Scorer, ScorerConfig, Scorers},
+    suite::CategoryConfig,
+    tested_model::TestedModel,
+    transport::HttpConfig,
+};
+
+/// A configured evaluation run over a set of cases.
+pub struct EvalRun {
+    cases: Vec<EvalCase>,
+    config: Arc<ModelConfig>,
+    tested_model: Arc<TestedModel>,
+    judge: Arc<JudgeModel>,
+    scorers: Arc<Scorers>,
+    threshold: f64,
+    suite: Option<String>,
+    prompt_version: Option<String>,
+    judge_prompt_version: Option<String>,
+    early_stopping: Option<EarlyStopping>,
+    seed: Option<u64>,
+    breakdown_by: Vec<String>,
+    serial_locks: Arc<HashMap<String, Arc<Mutex<()>>>>,
+    pending_teardowns: PendingTeardowns,
+}
+
+/// Teardowns of cancelled cases, running in the background.
+type PendingTeardowns = Arc<SyncMutex<Vec<JoinHandle<()>>>>;
+
+impl EvalRun {
+    pub fn builder(config: ModelConfig) -> EvalRunBuilder {
+        EvalRunBuilder {
+            config,
+            cases: Vec::new(),
+            threshold: 0.8,
+            suite: None,
+            prompt_version: None,
+            judge_prompt_version: None,
+            judge_prompt: JudgePrompt::default(),
+            scorer: None,
+            mcp_manager: None,
+            model: None,
+            judge_model: None,
+            judge_model_name: None,
+            http: HttpConfig::default(),
+            early_stopping: None,
+            seed: None,
+            breakdown_by: Vec::new(),
+            categories: HashMap::new(),
+        }
+    }
+
+    pub fn cases(&self) -> &[EvalCase] {
+        &self.cases
+    }
+
+    pub fn config(&self) -> &ModelConfig {
+        &self.config
+    }
+
+    pub fn judge(&self) -> &JudgeModel {
+        &self.judge
+    }
+
+    pub fn threshold(&self) -> f64 {
+        self.threshold
+    }
+
+    /// Evaluates every case concurrently, yielding results as they complete.
+    /// Cases in serial categories run one at a time within their category.
+    ///
+    /// With early stopping, cases run in random order with bounded
+    /// concurrency and the stream ends once the pass rate is known within the
+    /// configured margin. A seed makes the order repeatable.
+    ///
+    /// Cases cancelled by dropping the stream run their teardown in the
+    /// background; await [`EvalRun::finish_teardowns`] after dropping it.
+    pub fn stream(&self) -> impl Stream<Item = Result<EvalResult>> + use<> {
+        let mut cases = self.cases.clone();
+        if self.early_stopping.is_some() {
+            match self.seed {
+                Some(seed) => cases.shuffle(&mut StdRng::seed_from_u64(seed)),
+                None => cases.shuffle(&mut rand::rng()),
+            }
+        }
+
+        run_eval_stream(
+            cases,
+            Arc::clone(&self.tested_model),
+            Arc::clone(&self.config),
+            Arc::clone(&self.scorers),
+            self.threshold,
+            self.early_stopping,
+            Arc::clone(&self.serial_locks),
+            Arc::clone(&self.pending_teardowns),
+        )
+    }
+
+    /// Waits for the teardowns of cases cancelled by dropping a stream.
+    pub async fn finish_teardowns(&self) {
+        let pending = std::mem::take(&mut *self.pending_teardowns.lock().unwrap());
+        for teardown in pending {
+            let _ = teardown.await;
+        }
+    }
+
+    pub fn report(&self, results: &[EvalResult], execution_time: f64) -> Result<EvaluationReport> {
+        let mut report = generate_report(
+            results,
+            &self.config,
+            self.judge.prompt(),
+            self.threshold,
+            execution_time,
+        )?;
+        report.metadata.suite = self.suite.clone();
+        report.metadata.judge_model = Some(self.judge.model_name().to_string());
+        report.summary.breakdowns = metadata_breakdowns(results, &self.breakdown_by);
+        if let Some(system_prompt) = &mut report.metadata.system_prompt {
+            system_prompt.version = self.prompt_version.clone();
+        }
+        if let Some(judge_prompt) = &mut report.metadata.judge_prompt {
+            judge_prompt.version = self.judge_prompt_version.clone();
+        }
+        report.early_stopping = self.early_stopping.as_ref().map(|early_stopping| {
+            EarlyStoppingReport::new(
+                early_stopping,
+                report.summary.passed_count,
+                results.len(),
+                self.cases.len(),
+            )
+        });
+        Ok(report)
+    }
+
+    /// Runs every case to completion and builds the report.
+    pub async fn execute(&self) -> Result<EvaluationReport> {
+        let start_time = std::time::Instant::now();
+        let results = self.collect_results().await;
+        self.finish_teardowns().await;
+
+        self.report(&results?, start_time.elapsed().as_secs_f64())
+    }
+
+    async fn collect_results(&self) -> Result<Vec<EvalResult>> {
+        let stream = self.stream();
+        tokio::pin!(stream);
+
+        let mut results = Vec::with_capacity(self.cases.len());
+        while let Some(result) = stream.next().await {
+            results.push(result?);
+        }
+        Ok(results)
+    }
+}
+
+pub struct EvalRunBuilder {
+    config: ModelConfig,
+    cases: Vec<EvalCase>,
+    threshold: f64,
+    suite: Option<String>,
+    prompt_version: Option<String>,
+    judge_prompt_version: Option<String>,
+    judge_prompt: JudgePrompt,
+    scorer: Option<ScorerConfig>,
+    mcp_manager: Option<Arc<McpManager>>,
+    model: Option<Arc<dyn ConversationModel>>,
+    judge_model: Option<Arc<dyn ConversationModel>>,
+    judge_model_name: Option<String>,
+    http: HttpConfig,
+    early_stopping: Option<EarlyStopping>,
+    seed: Option<u64>,
+    breakdown_by: Vec<String>,
+    categories: HashMap<String, CategoryConfig>,
+}
+
+impl EvalRunBuilder {
+    pub fn cases(mut self, cases: Vec<EvalCase>) -> Self {
+        self.cases = cases;
+        self
+    }
+
+    pub fn threshold(mut self, threshold: f64) -> Self {
+        self.threshold = threshold;
+        self
+    }
+
+    /// Names the suite the cases belong to, used to group runs in history.
+    pub fn suite(mut self, suite: impl Into<String>) -> Self {
+        self.suite = Some(suite.into());
+        self
+    }
+
+    /// Labels the system prompt in the report, next to its hash.
+    pub fn prompt_version(mut self, version: impl Into<String>) -> Self {
+        self.prompt_version = Some(version.into());
+        self
+    }
+
+    /// Labels the judge prompt in the report, next to its hash.
+    pub fn judge_prompt_version(mut self, version: impl Into<String>) -> Self {
+        self.judge_prompt_version = Some(version.into());
+        self
+    }
+
+    pub fn judge_prompt(mut self, judge_prompt: JudgePrompt) -> Self {
+        self.judge_prompt = judge_prompt;
+        self
+    }
+
+    pub fn scorer(mut self, scorer: ScorerConfig) -> Self {
+        self.scorer = Some(scorer);
+        self
+    }
+
+    pub fn mcp_manager(mut self, mcp_manager: Arc<McpManager>) -> Self {
+        self.mcp_manager = Some(mcp_manager);
+        self
+    }
+
+    /// Uses `model` for generation instead of creating one for the configured provider.
+    pub fn model(mut self, model: Arc<dyn ConversationModel>) -> Self {
+        self.model = Some(model);
+        self
+    }
+
+    /// Uses `judge_model` for judging instead of creating an Anthropic model.
+    pub fn judge_model(mut self, judge_model: Arc<dyn ConversationModel>) -> Self {
+        self.judge_model = Some(judge_model);
+        self
+    }
+
+    /// Judges with `model_name` instead of [`JUDGE_MODEL`](crate::judge::JUDGE_MODEL).
+    pub fn judge_model_name(mut self, model_name: impl Into<String>) -> Self {
+        self.judge_model_name = Some(model_name.into());
+        self
+    }
+
+    /// Network settings for the models created when none is given.
+    pub fn http_config(mut self, http: HttpConfig) -> Self {
+        self.http = http;
+        self
+    }
+
+    pub fn early_stopping(mut self, early_stopping: EarlyStopping) -> Self {
+        self.early_stopping = Some(early_stopping);
+        self
+    }
+
+    /// Seeds the case order used with early stopping.
+    pub fn seed(mut self, seed: u64) -> Self {
+        self.seed = Some(seed);
+        self
+    }
+
+    /// Metadata keys to break the pass rate down by in the report.
+    pub fn breakdown_by(mut self, keys: Vec<String>) -> Self {
+        self.breakdown_by = keys;
+        self
+    }
+
+    /// Configures categories by name, e.g. to run some of them serially.
+    pub fn categories(mut self, categories: HashMap<String, CategoryConfig>) -> Self {
+        self.categories = categories;
+        self
+    }
+
+    pub fn build(self) -> Result<EvalRun> {
+        self.config.validate()?;
+        let model = match self.model {
+            Some(model) => model,
+            None => create_model_with_http_config(self.config.provider, &self.http)?,
+        };
+        let tested_model = Arc::new(match self.mcp_manager {
+            Some(mcp_manager) => TestedModel::with_mcp(model, mcp_manager),
+            None => TestedModel::new(model),
+        });
+
+        let judge_model = match self.judge_model {
+            Some(judge_model) => judge_model,
+            None => create_model_with_http_config(Provider::Anthropic, &self.http)?,
+        };
+        let mut judge =
+            JudgeModel::new(judge_model, self.judge_prompt).with_threshold(self.threshold);
+        if let Some(model_name) = self.judge_model_name {
+            judge = judge.with_model_name(model_name);
+        }
+        let judge = Arc::new(judge);
+        let scorers = Arc::new(Scorers::load(Arc::clone(&judge), self.scorer, &self.cases)?);
+
+        Ok(EvalRun {
+            cases: self.cases,
+            config: Arc::new(self.config),
+            tested_model,
+            judge,
+            scorers,
+            threshold: self.threshold,
+            suite: self.suite,
+            prompt_version: self.prompt_version,
+            judge_prompt_version: self.judge_prompt_version,
+            early_stopping: self.early_stopping,
+            seed: self.seed,
+            breakdown_by: self.breakdown_by,
+            serial_locks: Arc::new(
+                self.categories
+                    .into_iter()
+                    .filter(|(_, config)| config.serial)
+                    .map(|(category, _)| (category, Arc::new(Mutex::new(()))))
+                    .collect(),
+            ),
+            pending_teardowns: Arc::default(),
+        })
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn run_eval_stream(
+    cases: Vec<EvalCase>,
+    tested_model: Arc<TestedModel>,
+    config: Arc<ModelConfig>,
+    scorers: Arc<Scorers>,
+    threshold: f64,
+    early_stopping: Option<EarlyStopping>,
+    serial_locks: Arc<HashMap<String, Arc<Mutex<()>>>>,
+    pending_teardowns: PendingTeardowns,
+) -> impl Stream<Item = Result<EvalResult>> {
+    let concurrency = early_stopping
+        .map_or(cases.len(), |early_stopping| early_stopping.concurrency)
+        .max(1);
+
+    stream::iter(cases)
+        .map(move |case| {
+            let tested_model = Arc::clone(&tested_model);
+            let config = Arc::clone(&config);
+            let scorer = scorers.for_case(&case);
+            let scorer_config = scorers.config_for_case(&case);
+            let serial_lock = case
+                .metadata
+                .get("category")
+                .and_then(|category| serial_locks.get(category))
+                .cloned();
+            let pending_teardowns = Arc::clone(&pending_teardowns);
+
+            async move {
+                let _serial_guard = match &serial_lock {
+                    Some(serial_lock) => Some(serial_lock.lock().await),
+                    None => None,
+                };
+
+                if let Some(setup) = &case.setup {
+                    run_case_command(setup, &case).await?;
+                }
+                let teardown = TeardownGuard {
+                    command: case.teardown.as_ref(),
+                    case: &case,
+                    pending: &pending_teardowns,
+                };
+
+                let result = evaluate_case(
+                    &case,
+                    &tested_model,
+                    &config,
+                    scorer.as_ref(),
+                    scorer_config,
+                    threshold,
+                )
+                .await;
+
+                let teardown_result = teardown.run().await;
+                if result.is_ok() {
+                    teardown_result?;
+                }
+
+                result
+            }
+        })
+        .buffer_unordered(concurrency)
+        .scan((0, 0, false), move |(passed, total, confident), result| {
+            if *confident {
+                return ready(None);
+            }
+            if let (Some(early_stopping), Ok(result)) = (&early_stopping, &result) {
+                *total += 1;
+                *passed += usize::from(result.passed);
+                *confident = early_stopping.is_confident(*passed, *total);
+            }
+            ready(Some(result))
+        })
+}
+
+async fn evaluate_case(
+    case: &EvalCase,
+    tested_model: &TestedModel,
+    config: &ModelConfig,
+    scorer: &dyn Scorer,
+    scorer_config: Option<ScorerConfig>,
+    threshold: f64,
+) -> Result<EvalResult> {
+    let iterations_count = config.iterations.unwrap_or(1);
+
+    let mut iteration_results = Vec::new();
+    let mut passed_count = 0;
+    let mut total_score = 0.0;
+
+    for _ in 0..iterations_count {
+        let mut response = tested_model.respond(&case.input, config).await?;
+        let mut max_tokens = config.max_tokens;
+        while response.metadata.stop_reason == Some(StopReason::MaxTokens)
+            && let Some(cap) = config.max_tokens_cap
+            && max_tokens < cap
+        {
+            // Always grows, so the retries end at the cap.
+            max_tokens = max_tokens.max(1).saturating_mul(2).min(cap);
+            let retry_config = ModelConfig {
+                max_tokens,
+                ..config.clone()
+            };
+            response = tested_model.respond(&case.input, &retry_config).await?;
+        }
+        let truncated = response.metadata.stop_reason == Some(StopReason::MaxTokens);
+        let actual_output = response.output_text();
+        let generation_failure = check_output(&case.input, &actual_output);
+        let score = match generation_failure {
+            Some(failure) => Score::new(0.0, failure.to_string()),
+            None => scorer.score(case, &actual_output).await?,
+        };
+        let passed = generation_failure.is_none() && score.score >= threshold;
+
+        if passed {
+            passed_count += 1;
+        }
+        total_score += score.score;
+
+        iteration_results.push(IterationResult {
+            actual_output,
+            judge_score: score.score,
+            judge_reasoning: score.reasoning,
+            judge_output: score.details,
+            passed,
+            failure_category: score.failure_category.filter(|_| !passed),
+            generation: response.metadata,
+            truncated,
+            max_tokens: (max_tokens != config.max_tokens).then_some(max_tokens),
+            generation_failure,
+            logprobs: response.logprobs,
+        });
+    }
+
+    let average_score = total_score / iterations_count as f64;
+    let overall_passed = passed_count > 0; // Pass if any iteration passes
+
+    let case_report = EvalCaseReport {
+        input: case.input.clone(),
+        expected_output: case.expected_output.as_ref().and_then(|e| e.to_object()),
+        metadata: case.metadata.clone(),
+        scorer: scorer_config,
+    };
+
+    let pass_at_k_stats = if iterations_count > 1 {
+        Some(PassAtKStats {
+            total_iterations: iterations_count,
+            passed_iterations: passed_count,
+            pass_rate: passed_count as f64 / iterations_count as f64,
+        })
+    } else {
+        None
+    };
+
+    let mean_logprob = mean_logprob(&iteration_results);
+
+    Ok(EvalResult {
+        case: case_report,
+        judge_score: average_score,
+        passed: overall_passed,
+        iterations: iteration_results,
+        pass_at_k: pass_at_k_stats,
+        difficulty: None,
+        mean_logprob,
+        notes: Vec::new(),
+    })
+}
+
+fn mean_logprob(iterations: &[IterationResult]) -> Option<f64> {
+    let logprobs: Vec<f64> = iterations
+        .iter()
+        .filter_map(|iteration| iteration.logprobs.as_ref())
+        .flatten()
+        .map(|token| token.logprob)
+        .collect();
+    if logprobs.is_empty() {
+        None
+    } else {
+        Some(logprobs.iter().sum::<f64>() / logprobs.len() as f64)
+    }
+}
+
+/// Runs a case's setup or teardown command with the case as JSON on stdin.
+async fn run_case_command(command: &HookCommand, case: &EvalCase) -> Result<()> {
+    run_shell_command(
+        &command.command,
+        &serde_json::to_vec(case)?,
+        command.timeout_seconds.map(Duration::from_secs),
+    )
+    .await
+    .map_err(|e| anyhow!("Case '{}': {}", case.input, e))
+}
+
+/// A case's pending teardown. It runs inline once the case finishes; if the
+/// case is cancelled first, because early stopping ended the run or another
+/// case failed, the dropped guard spawns it onto the runtime and adds it to
+/// `pending`.
+struct TeardownGuard<'a> {
+    command: Option<&'a HookCommand>,
+    case: &'a EvalCase,
+    pending: &'a PendingTeardowns,
+}
+
+impl TeardownGuard<'_> {
+    async fn run(mut self) -> Result<()> {
+        let Some(command) = self.command else {
+            return Ok(());
+        };
+        let result = run_case_command(command, self.case).await;
+        self.command = None;
+        result
+    }
+}
+
+impl Drop for TeardownGuard<'_> {
+    fn drop(&mut self) {
+        let Some(command) = self.command.take() else {
+            return;
+        };
+        let command = command.clone();
+        let case = self.case.clone();
+
+        // Cases only start inside a runtime, so one is current when they are
+        // cancelled. The teardown's error is dropped with the case.
+        let Ok(runtime) = tokio::runtime::Handle::try_current() else {
+            return;
+        };
+        let teardown = runtime.spawn(async move {
+            let _ = run_case_command(&command, &case).await;
+        });
+        self.pending.lock().unwrap().push(teardown);
+    }
+}
+
+pub fn breakdown_by<F>(results: &[EvalResult], key: F) -> HashMap<String, CategoryStats>
+where
+    F: Fn(&EvalResult) -> Option<String>,
+{
+    let mut breakdown = HashMap::new();
+    for result in results {
+        if let Some(group) = key(result) {
+            let entry = breakdown.entry(group).or_insert(CategoryStats {
+                total: 0,
+                passed: 0,
+                pass_rate_percent: 0.0,
+            });
+            entry.total += 1;
+            if result.passed {
+                entry.passed += 1;
+            }
+            entry.pass_rate_percent = (entry.passed as f64 / entry.total as f64) * 100.0;
+        }
+    }
+    breakdown
+}
+
+/// Breakdowns by each metadata key in `keys`; keys that no case has are
+/// left out.
+pub fn metadata_breakdowns(
+    results: &[EvalResult],
+    keys: &[String],
+) -> BTreeMap<String, HashMap<String, CategoryStats>> {
+    keys.iter()
+        .filter_map(|key| {
+            let breakdown = breakdown_by(results, |result| result.case.metadata.get(key).cloned());
+            (!breakdown.is_empty()).then(|| (key.clone(), breakdown))
+        })
+        .collect()
+}
+
+pub fn generate_report(
+    results: &[EvalResult],
+    config: &ModelConfig,
+    judge_prompt: &JudgePrompt,
+    threshold: f64,
+    execution_time: f64,
+) -> Result<EvaluationReport> {
+    let total_cases = results.len();
+    let passed_count = results.iter().filter(|r| r.passed).count();
+    let failed_count = total_cases - passed_count;
+    let pass_rate = (passed_count as f64 / total_cases as f64) * 100.0;
+
+    let scores: Vec<f64> = results.iter().map(|r| r.judge_score).collect();
+    let average_score = scores.iter().sum::<f64>() / scores.len() as f64;
+    let min_score = scores.iter().fold(f64::INFINITY, |a, &b| a.min(b));
+    let max_score = scores.iter().fold(f64::NEG_INFINITY, |a, &b| a.max(b));
+
+    let category_breakdown = breakdown_by(results, |result| {
+        result.case.metadata.get("category").cloned()
+    });
+    let difficulty_breakdown = breakdown_by(results, |result| {
+        result
+            .difficulty
+            .as_ref()
+            .map(|difficulty| difficulty.bucket().to_string())
+    });
+
+    let generation_failures = results
+        .iter()
+        .flat_map(|result| result.iterations.iter())
+        .filter(|iteration| iteration.generation_failure.is_some())
+        .count();
+
+    let truncated = results
+        .iter()
+        .flat_map(|result| result.iterations.iter())
+        .filter(|iteration| iteration.truncated)
+        .count();
+
+    let report = EvaluationReport {
+        schema_version: SCHEMA_VERSION,
+        metadata: ReportMetadata {
+            generated_at: Utc::now(),
+            total_cases,
+            threshold,
+            execution_time_seconds: execution_time,
+            suite: None,
+            system_prompt: config
+                .system
+                .as_ref()
+                .map(|system| PromptVersion::new(&system.text())),
+            judge_prompt: Some(PromptVersion::new(&judge_prompt.text())),
+            judge_model: None,
+            notes: Vec::new(),
+        },
+        configuration: config.clone(),
+        judge_configuration: judge_prompt.clone(),
+        summary: ReportSummary {
+            passed_count,
+            failed_count,
+            pass_rate_percent: pass_rate,
+            average_score,
+            min_score,
+            max_score,
+            category_breakdown,
+            breakdowns: BTreeMap::new(),
+            difficulty_breakdown,
+            generation_failures,
+            truncated,
+            failure_categories: failure_category_counts(results),
+        },
+        results: results.to_vec(),
+        failure_modes: Vec::new(),
+        early_stopping: None,
+    };
+
+    Ok(report)
+}
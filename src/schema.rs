@@ -0,0 +1,71 @@
+//! The report format read by dashboards and scripts.
+//!
+//! Reports carry a `schema_version`. Fields may be added within a version,
+//! always optional or defaulted so older reports keep parsing; renaming or
+//! removing a field, or changing its meaning, bumps [`SCHEMA_VERSION`].
+
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+pub use crate::{
+    conversation_model::{GenerationMetadata, StopReason, TokenLogprob, TokenUsage},
+    evaluation::{
+        CategoryStats, EvalCaseReport, EvalResult, EvaluationReport, ExpectedOutputObject,
+        IterationResult, Note, PassAtKStats, PromptVersion, ReportMetadata, ReportSummary,
+    },
+    judge::FailureCategory,
+    output_guard::GenerationFailure,
+};
+
+/// Version of the report format written by this crate. Reports written
+/// before versioning have `schema_version` 0.
+pub const SCHEMA_VERSION: u32 = 1;
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+#[non_exhaustive]
+pub enum EvalError {
+    /// The report is not valid JSON or does not match the schema.
+    InvalidReport { message: String },
+    /// The report was written by a newer version of the crate.
+    UnsupportedSchemaVersion { found: u32, supported: u32 },
+}
+
+impl fmt::Display for EvalError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EvalError::InvalidReport { message } => write!(f, "Invalid report: {}", message),
+            EvalError::UnsupportedSchemaVersion { found, supported } => write!(
+                f,
+                "Report schema version {} is newer than the supported version {}",
+                found, supported
+            ),
+        }
+    }
+}
+
+impl std::error::Error for EvalError {}
+
+impl EvaluationReport {
+    /// Parses a report, rejecting reports from a newer schema version before
+    /// they can be misread.
+    pub fn from_json(content: &str) -> Result<Self, EvalError> {
+        let value: serde_json::Value =
+            serde_json::from_str(content).map_err(|e| EvalError::InvalidReport {
+                message: e.to_string(),
+            })?;
+
+        let found = value["schema_version"].as_u64().unwrap_or(0);
+        if found > u64::from(SCHEMA_VERSION) {
+            return Err(EvalError::UnsupportedSchemaVersion {
+                found: u32::try_from(found).unwrap_or(u32::MAX),
+                supported: SCHEMA_VERSION,
+            });
+        }
+
+        serde_json::from_value(value).map_err(|e| EvalError::InvalidReport {
+            message: e.to_string(),
+        })
+    }
+}
@@ -0,0 +1,102 @@
+pub mod wasm;
+
+use std::{collections::HashMap, sync::Arc};
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    evaluation::EvalCase,
+    judge::{FailureCategory, JudgeModel},
+};
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Hash)]
+#[serde(tag = "type")]
+pub enum ScorerConfig {
+    #[serde(rename = "wasm")]
+    Wasm { path: String },
+}
+
+#[derive(Debug, Clone)]
+pub struct Score {
+    pub score: f64,
+    pub reasoning: String,
+    pub failure_category: Option<FailureCategory>,
+    /// The scorer's full structured output, when it has more than a score and reasoning.
+    pub details: Option<serde_json::Value>,
+}
+
+impl Score {
+    pub fn new(score: f64, reasoning: impl Into<String>) -> Self {
+        Self {
+            score,
+            reasoning: reasoning.into(),
+            failure_category: None,
+            details: None,
+        }
+    }
+}
+
+#[async_trait::async_trait]
+pub trait Scorer: Send + Sync {
+    async fn score(&self, case: &EvalCase, actual_output: &str) -> Result<Score>;
+}
+
+#[async_trait::async_trait]
+impl Scorer for JudgeModel {
+    async fn score(&self, case: &EvalCase, actual_output: &str) -> Result<Score> {
+        self.evaluate(case, actual_output).await
+    }
+}
+
+/// Resolves the scorer for each case, falling back from the case's own scorer
+/// to the run-level scorer and finally to the judge model.
+pub struct Scorers {
+    judge: Arc<JudgeModel>,
+    run_scorer: Option<ScorerConfig>,
+    plugins: HashMap<ScorerConfig, Arc<dyn Scorer>>,
+}
+
+impl Scorers {
+    pub fn load(
+        judge: Arc<JudgeModel>,
+        run_scorer: Option<ScorerConfig>,
+        cases: &[EvalCase],
+    ) -> Result<Self> {
+        let mut plugins: HashMap<ScorerConfig, Arc<dyn Scorer>> = HashMap::new();
+
+        let configs = run_scorer
+            .iter()
+            .chain(cases.iter().filter_map(|case| case.scorer.as_ref()));
+
+        for config in configs {
+            if plugins.contains_key(config) {
+                continue;
+            }
+
+            let scorer: Arc<dyn Scorer> = match config {
+                ScorerConfig::Wasm { path } => Arc::new(wasm::WasmScorer::load(path)?),
+            };
+            plugins.insert(config.clone(), scorer);
+        }
+
+        Ok(Self {
+            judge,
+            run_scorer,
+            plugins,
+        })
+    }
+
+    pub fn for_case(&self, case: &EvalCase) -> Arc<dyn Scorer> {
+        case.scorer
+            .as_ref()
+            .or(self.run_scorer.as_ref())
+            .and_then(|config| self.plugins.get(config))
+            .cloned()
+            .unwrap_or_else(|| Arc::clone(&self.judge) as Arc<dyn Scorer>)
+    }
+
+    pub fn config_for_case(&self, case: &EvalCase) -> Option<ScorerConfig> {
+        case.scorer.clone().or_else(|| self.run_scorer.clone())
+    }
+}
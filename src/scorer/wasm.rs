@@ -0,0 +1,119 @@
+use anyhow::{Result, anyhow};
+use serde::{Deserialize, Serialize};
+use wasmtime::{Config, Engine, Instance, Module, Store, Trap};
+
+use super::{Score, Scorer};
+use crate::evaluation::{EvalCase, ExpectedOutput, ExpectedOutputObject};
+
+/// Instructions a module may run per call.
+pub const FUEL: u64 = 1_000_000_000;
+
+/// Scorer backed by a WebAssembly module.
+///
+/// The module must export `memory`, `alloc(len: i32) -> i32` and
+/// `score(ptr: i32, len: i32) -> i64`. The host writes a JSON request
+/// (`input`, `expected`, `actual`) into a buffer obtained from `alloc` and
+/// calls `score`, which returns the location of a JSON response
+/// (`score`, `reasoning`) packed as `(ptr << 32) | len`.
+///
+/// Each call may run at most [`FUEL`] instructions, so a module that never
+/// returns fails its case instead of hanging the run.
+pub struct WasmScorer {
+    path: String,
+    engine: Engine,
+    module: Module,
+}
+
+#[derive(Debug, Serialize)]
+struct ScoreRequest<'a> {
+    input: &'a str,
+    expected: Option<&'a str>,
+    actual: &'a str,
+}
+
+#[derive(Debug, Deserialize)]
+struct ScoreResponse {
+    score: f64,
+    #[serde(default)]
+    reasoning: String,
+}
+
+impl WasmScorer {
+    pub fn load(path: &str) -> Result<Self> {
+        let engine = Engine::new(Config::new().consume_fuel(true))
+            .map_err(|e| anyhow!("Failed to create WASM engine: {}", e))?;
+        let module = Module::from_file(&engine, path)
+            .map_err(|e| anyhow!("Failed to load WASM scorer '{}': {}", path, e))?;
+
+        Ok(Self {
+            path: path.to_string(),
+            engine,
+            module,
+        })
+    }
+
+    fn call(engine: &Engine, module: &Module, request: &[u8]) -> wasmtime::Result<ScoreResponse> {
+        let mut store = Store::new(engine, ());
+        store.set_fuel(FUEL)?;
+        let instance = Instance::new(&mut store, module, &[])?;
+
+        let memory = instance
+            .get_memory(&mut store, "memory")
+            .ok_or_else(|| wasmtime::Error::msg("module does not export `memory`"))?;
+        let alloc = instance.get_typed_func::<i32, i32>(&mut store, "alloc")?;
+        let score = instance.get_typed_func::<(i32, i32), i64>(&mut store, "score")?;
+
+        let request_len = i32::try_from(request.len())?;
+        let request_ptr = alloc.call(&mut store, request_len)?;
+        memory.write(&mut store, request_ptr as u32 as usize, request)?;
+
+        let packed = score.call(&mut store, (request_ptr, request_len))?;
+        let response_ptr = (packed >> 32) as u32 as usize;
+        let response_len = packed as u32 as usize;
+
+        let mut response = vec![0u8; response_len];
+        memory.read(&store, response_ptr, &mut response)?;
+
+        Ok(serde_json::from_slice(&response)?)
+    }
+}
+
+#[async_trait::async_trait]
+impl Scorer for WasmScorer {
+    async fn score(&self, case: &EvalCase, actual_output: &str) -> Result<Score> {
+        let expected = case
+            .expected_output
+            .as_ref()
+            .map(|expected| match expected {
+                ExpectedOutput::String(content) => content.as_str(),
+                ExpectedOutput::Object(ExpectedOutputObject::ContentComparison { description })
+                | ExpectedOutput::Object(ExpectedOutputObject::BehaviorDescription {
+                    description,
+                }) => description.as_str(),
+            });
+
+        let request = serde_json::to_vec(&ScoreRequest {
+            input: &case.input,
+            expected,
+            actual: actual_output,
+        })?;
+
+        let engine = self.engine.clone();
+        let module = self.module.clone();
+        let response = tokio::task::spawn_blocking(move || Self::call(&engine, &module, &request))
+            .await?
+            .map_err(|e| match e.downcast_ref::<Trap>() {
+                Some(Trap::OutOfFuel) => anyhow!(
+                    "WASM scorer '{}' ran out of fuel after {} instructions",
+                    self.path,
+                    FUEL
+                ),
+                _ => anyhow!("WASM scorer '{}' failed: {}", self.path, e),
+            })?;
+
+        Ok(Score::new(
+            response.score.clamp(0.0, 1.0),
+            response.reasoning,
+        ))
+    }
+}
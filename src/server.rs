@@ -0,0 +1,437 @@
+use std::{collections::HashMap, net::SocketAddr, path::PathBuf, sync::Arc};
+
+use anyhow::{Result, anyhow};
+use axum::{
+    Json, Router,
+    extract::{Path, Request, State},
+    http::{StatusCode, header},
+    middleware::{self, Next},
+    response::{IntoResponse, Response},
+    routing::get,
+};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use tokio::sync::RwLock;
+use tokio_stream::StreamExt;
+use uuid::Uuid;
+
+use crate::{
+    cases_source::read_cases_source,
+    conversation_model::create_model_with_http_config,
+    evaluation::{EvalResult, EvaluationReport},
+    history::{HistoryStore, check_suite_name},
+    model_config::{ModelConfig, Provider},
+    preprocess::Preprocessor,
+    runner::EvalRun,
+    suite::Suite,
+    transport::HttpConfig,
+};
+
+fn default_threshold() -> f64 {
+    0.8
+}
+
+/// Body of `POST /runs`. Cases are given inline, in any cases file shape, or
+/// as the path of a cases file in the server's cases directory.
+#[derive(Debug, Deserialize)]
+pub struct RunRequest {
+    #[serde(default)]
+    pub suite: Option<String>,
+    #[serde(default)]
+    pub cases: Option<serde_json::Value>,
+    #[serde(default)]
+    pub cases_file: Option<String>,
+    pub configuration: ModelConfig,
+    #[serde(default = "default_threshold")]
+    pub threshold: f64,
+    /// Store the report in the server's run history.
+    #[serde(default)]
+    pub save_history: bool,
+}
+
+#[derive(Debug, Serialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum RunStatus {
+    Running,
+    Completed,
+    Failed,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct RunInfo {
+    pub id: Uuid,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub suite: Option<String>,
+    pub status: RunStatus,
+    pub started_at: DateTime<Utc>,
+    pub total_cases: usize,
+    pub completed_cases: usize,
+    pub passed_cases: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// What API callers are allowed to do.
+#[derive(Debug, Clone)]
+pub struct ServerPolicy {
+    /// Directory that `cases_file` paths and `include` tags resolve in; without
+    /// it, only inline cases without includes are accepted.
+    pub cases_dir: Option<PathBuf>,
+    /// Accept cases with `setup`/`teardown` commands or WASM scorers, which
+    /// run on the server.
+    pub allow_case_commands: bool,
+    /// Runs kept in memory; the oldest finished runs are evicted beyond it.
+    pub max_runs: usize,
+}
+
+impl Default for ServerPolicy {
+    fn default() -> Self {
+        Self {
+            cases_dir: None,
+            allow_case_commands: false,
+            max_runs: 100,
+        }
+    }
+}
+
+struct RunEntry {
+    info: RunInfo,
+    report: Option<Arc<EvaluationReport>>,
+}
+
+#[derive(Clone)]
+struct ServerState {
+    runs: Arc<RwLock<HashMap<Uuid, RunEntry>>>,
+    http: Arc<HttpConfig>,
+    history: Arc<HistoryStore>,
+    token: Option<Arc<str>>,
+    policy: Arc<ServerPolicy>,
+}
+
+struct ApiError(StatusCode, String);
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        (self.0, Json(serde_json::json!({ "error": self.1 }))).into_response()
+    }
+}
+
+impl From<anyhow::Error> for ApiError {
+    fn from(error: anyhow::Error) -> Self {
+        ApiError(StatusCode::BAD_REQUEST, error.to_string())
+    }
+}
+
+/// Serves the REST API until the process is stopped:
+///
+/// - `POST /runs` starts a run and returns its status
+/// - `GET /runs` lists every run started since the server came up
+/// - `GET /runs/{id}` returns a run's status and progress
+/// - `GET /runs/{id}/report` returns the report of a completed run
+///
+/// With a `token`, every request needs an `Authorization: Bearer <token>` header.
+/// A token is required to listen on anything but a loopback address.
+pub async fn serve(
+    address: SocketAddr,
+    http: HttpConfig,
+    history: HistoryStore,
+    token: Option<String>,
+    policy: ServerPolicy,
+) -> Result<()> {
+    if token.is_none() && !address.ip().is_loopback() {
+        return Err(anyhow!(
+            "A token is required to listen on the non-loopback address {}",
+            address
+        ));
+    }
+
+    let state = ServerState {
+        runs: Arc::new(RwLock::new(HashMap::new())),
+        http: Arc::new(http),
+        history: Arc::new(history),
+        token: token.map(Into::into),
+        policy: Arc::new(policy),
+    };
+
+    let router = Router::new()
+        .route("/runs", get(list_runs).post(start_run))
+        .route("/runs/{id}", get(get_run))
+        .route("/runs/{id}/report", get(get_report))
+        .layer(middleware::from_fn_with_state(state.clone(), authorize))
+        .with_state(state);
+
+    let listener = tokio::net::TcpListener::bind(address)
+        .await
+        .map_err(|e| anyhow!("Failed to bind {}: {}", address, e))?;
+    axum::serve(listener, router).await?;
+
+    Ok(())
+}
+
+async fn authorize(
+    State(state): State<ServerState>,
+    request: Request,
+    next: Next,
+) -> Result<Response, ApiError> {
+    if let Some(token) = &state.token {
+        let authorized = request
+            .headers()
+            .get(header::AUTHORIZATION)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.strip_prefix("Bearer "))
+            .is_some_and(|provided| provided == token.as_ref());
+        if !authorized {
+            return Err(ApiError(
+                StatusCode::UNAUTHORIZED,
+                "Missing or invalid bearer token".to_string(),
+            ));
+        }
+    }
+
+    Ok(next.run(request).await)
+}
+
+async fn start_run(
+    State(state): State<ServerState>,
+    Json(request): Json<RunRequest>,
+) -> Result<(StatusCode, Json<RunInfo>), ApiError> {
+    let policy = &state.policy;
+    if let Some(suite) = &request.suite {
+        check_suite_name(suite)?;
+    }
+    let (mut suite_config, base_dir) = match (request.cases, &request.cases_file) {
+        (Some(cases), None) => (Suite::from_value(cases)?, None),
+        (None, Some(cases_file)) => {
+            let path = resolve_cases_file(policy, cases_file).await?;
+            let content = read_cases_source(&path.to_string_lossy(), &[], &state.http).await?;
+            (
+                Suite::from_json(&content)?,
+                path.parent().map(PathBuf::from),
+            )
+        }
+        _ => {
+            return Err(ApiError(
+                StatusCode::BAD_REQUEST,
+                "Exactly one of \"cases\" and \"cases_file\" is required".to_string(),
+            ));
+        }
+    };
+
+    if !policy.allow_case_commands && suite_config.runs_code() {
+        return Err(ApiError(
+            StatusCode::FORBIDDEN,
+            "Cases with \"setup\", \"teardown\" or \"scorer\" need the server to allow case commands"
+                .to_string(),
+        ));
+    }
+
+    // Includes resolve next to the cases file, or in the cases directory for
+    // inline cases, and never outside the cases directory.
+    let variables = suite_config.variables.clone();
+    let preprocessor = match &policy.cases_dir {
+        Some(cases_dir) => {
+            Preprocessor::new(variables, base_dir.unwrap_or_else(|| cases_dir.clone()))
+                .confine_to(cases_dir)
+        }
+        None => Preprocessor::new(variables, ".").without_includes(),
+    };
+    suite_config.preprocess_with(preprocessor).await?;
+
+    let mut config = request.configuration;
+    if config.session.is_none() {
+        config.session = suite_config.session;
+    }
+
+    let mut run_builder = EvalRun::builder(config.clone())
+        .cases(suite_config.cases)
+        .categories(suite_config.categories)
+        .breakdown_by(suite_config.breakdown_by)
+        .threshold(request.threshold)
+        .model(create_model_with_http_config(config.provider, &state.http)?)
+        .judge_model(create_model_with_http_config(
+            Provider::Anthropic,
+            &state.http,
+        )?);
+    if let Some(suite) = &request.suite {
+        run_builder = run_builder.suite(suite.clone());
+    }
+    let run = run_builder.build()?;
+
+    let info = RunInfo {
+        id: Uuid::new_v4(),
+        suite: request.suite,
+        status: RunStatus::Running,
+        started_at: Utc::now(),
+        total_cases: run.cases().len(),
+        completed_cases: 0,
+        passed_cases: 0,
+        error: None,
+    };
+    {
+        let mut runs = state.runs.write().await;
+        evict_finished_runs(&mut runs, policy.max_runs.saturating_sub(1));
+        if runs.len() >= policy.max_runs {
+            return Err(ApiError(
+                StatusCode::SERVICE_UNAVAILABLE,
+                format!("{} runs are already in progress", runs.len()),
+            ));
+        }
+        runs.insert(
+            info.id,
+            RunEntry {
+                info: info.clone(),
+                report: None,
+            },
+        );
+    }
+
+    let id = info.id;
+    let save_history = request.save_history;
+    tokio::spawn(async move {
+        let outcome = execute_run(&state, id, run, save_history).await;
+
+        let mut runs = state.runs.write().await;
+        if let Some(entry) = runs.get_mut(&id) {
+            match outcome {
+                Ok(report) => {
+                    entry.info.status = RunStatus::Completed;
+                    entry.report = Some(Arc::new(report));
+                }
+                Err(error) => {
+                    entry.info.status = RunStatus::Failed;
+                    entry.info.error = Some(error.to_string());
+                }
+            }
+        }
+    });
+
+    Ok((StatusCode::ACCEPTED, Json(info)))
+}
+
+/// Resolves a `cases_file` inside the cases directory, refusing URLs and
+/// paths that escape it.
+async fn resolve_cases_file(policy: &ServerPolicy, cases_file: &str) -> Result<PathBuf, ApiError> {
+    let forbidden = |message: String| ApiError(StatusCode::FORBIDDEN, message);
+    let Some(cases_dir) = &policy.cases_dir else {
+        return Err(forbidden(
+            "\"cases_file\" needs the server to have a cases directory".to_string(),
+        ));
+    };
+
+    if cases_file.contains("://") {
+        return Err(forbidden(format!(
+            "Cases file '{}' must be a path in the cases directory",
+            cases_file
+        )));
+    }
+
+    let root = tokio::fs::canonicalize(cases_dir).await.map_err(|e| {
+        anyhow!(
+            "Failed to resolve cases directory '{}': {}",
+            cases_dir.display(),
+            e
+        )
+    })?;
+    let path = tokio::fs::canonicalize(cases_dir.join(cases_file))
+        .await
+        .map_err(|e| anyhow!("Failed to read cases file '{}': {}", cases_file, e))?;
+    if !path.starts_with(&root) {
+        return Err(forbidden(format!(
+            "Cases file '{}' is outside the cases directory",
+            cases_file
+        )));
+    }
+
+    Ok(path)
+}
+
+/// Removes the oldest finished runs until at most `limit` runs remain.
+fn evict_finished_runs(runs: &mut HashMap<Uuid, RunEntry>, limit: usize) {
+    let mut finished: Vec<(DateTime<Utc>, Uuid)> = runs
+        .values()
+        .filter(|entry| entry.info.status != RunStatus::Running)
+        .map(|entry| (entry.info.started_at, entry.info.id))
+        .collect();
+    finished.sort();
+
+    let excess = runs.len().saturating_sub(limit);
+    for (_, id) in finished.into_iter().take(excess) {
+        runs.remove(&id);
+    }
+}
+
+async fn execute_run(
+    state: &ServerState,
+    id: Uuid,
+    run: EvalRun,
+    save_history: bool,
+) -> Result<EvaluationReport> {
+    let start_time = std::time::Instant::now();
+    let results = collect_results(state, id, &run).await;
+    run.finish_teardowns().await;
+
+    let report = run.report(&results?, start_time.elapsed().as_secs_f64())?;
+    if save_history {
+        state.history.save(&report).await?;
+    }
+
+    Ok(report)
+}
+
+async fn collect_results(state: &ServerState, id: Uuid, run: &EvalRun) -> Result<Vec<EvalResult>> {
+    let stream = run.stream();
+    tokio::pin!(stream);
+
+    let mut results = Vec::with_capacity(run.cases().len());
+    while let Some(result) = stream.next().await {
+        let result = result?;
+        if let Some(entry) = state.runs.write().await.get_mut(&id) {
+            entry.info.completed_cases += 1;
+            if result.passed {
+                entry.info.passed_cases += 1;
+            }
+        }
+        results.push(result);
+    }
+    Ok(results)
+}
+
+async fn list_runs(State(state): State<ServerState>) -> Json<Vec<RunInfo>> {
+    let mut runs: Vec<RunInfo> = state
+        .runs
+        .read()
+        .await
+        .values()
+        .map(|entry| entry.info.clone())
+        .collect();
+    runs.sort_by_key(|info| info.started_at);
+    Json(runs)
+}
+
+async fn get_run(
+    State(state): State<ServerState>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<RunInfo>, ApiError> {
+    let runs = state.runs.read().await;
+    let entry = runs.get(&id).ok_or_else(|| run_not_found(id))?;
+    Ok(Json(entry.info.clone()))
+}
+
+async fn get_report(
+    State(state): State<ServerState>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<Arc<EvaluationReport>>, ApiError> {
+    let runs = state.runs.read().await;
+    let entry = runs.get(&id).ok_or_else(|| run_not_found(id))?;
+    match &entry.report {
+        Some(report) => Ok(Json(Arc::clone(report))),
+        None => Err(ApiError(
+            StatusCode::CONFLICT,
+            format!("Run {} has no report (status: {:?})", id, entry.info.status),
+        )),
+    }
+}
+
+fn run_not_found(id: Uuid) -> ApiError {
+    ApiError(StatusCode::NOT_FOUND, format!("Run {} not found", id))
+}
@@ -0,0 +1,50 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+/// Shared state every case of a suite starts from, such as remembered facts
+/// and a user profile, for evaluating personalised assistants.
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq)]
+pub struct SessionState {
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub facts: Vec<String>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub user_profile: BTreeMap<String, serde_json::Value>,
+}
+
+impl SessionState {
+    pub fn is_empty(&self) -> bool {
+        self.facts.is_empty() && self.user_profile.is_empty()
+    }
+
+    /// Renders the state as context text for the model.
+    pub fn render(&self) -> String {
+        let mut sections = Vec::new();
+
+        if !self.user_profile.is_empty() {
+            let fields: Vec<String> = self
+                .user_profile
+                .iter()
+                .map(|(key, value)| match value {
+                    serde_json::Value::String(value) => format!("- {}: {}", key, value),
+                    value => format!("- {}: {}", key, value),
+                })
+                .collect();
+            sections.push(format!("User profile:\n{}", fields.join("\n")));
+        }
+
+        if !self.facts.is_empty() {
+            let facts: Vec<String> = self
+                .facts
+                .iter()
+                .map(|fact| format!("- {}", fact))
+                .collect();
+            sections.push(format!("Known facts:\n{}", facts.join("\n")));
+        }
+
+        format!(
+            "Session state carried over from earlier conversations with this user.\n\n{}",
+            sections.join("\n\n")
+        )
+    }
+}
@@ -0,0 +1,148 @@
+use std::{collections::HashMap, sync::Arc};
+
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use futures::stream::FuturesUnordered;
+use serde::{Deserialize, Serialize};
+use tokio_stream::StreamExt;
+
+use crate::{
+    evaluation::{EvalCase, ExpectedOutput},
+    judge::JudgeModel,
+    model_config::ModelConfig,
+    tested_model::TestedModel,
+};
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct SnapshotFile {
+    pub blessed_at: DateTime<Utc>,
+    pub configuration: ModelConfig,
+    pub snapshots: Vec<Snapshot>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Snapshot {
+    pub input: String,
+    pub output: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(tag = "status")]
+pub enum SnapshotStatus {
+    #[serde(rename = "matched")]
+    Matched,
+    #[serde(rename = "changed")]
+    Changed {
+        blessed_output: String,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        reasoning: Option<String>,
+    },
+    #[serde(rename = "new")]
+    New,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct SnapshotComparison {
+    pub input: String,
+    pub actual_output: String,
+    #[serde(flatten)]
+    pub status: SnapshotStatus,
+}
+
+pub enum SnapshotMatcher {
+    /// Outputs must be identical, ignoring surrounding whitespace.
+    Exact,
+    /// The judge decides whether the new output is equivalent to the blessed one.
+    Judge {
+        judge: Arc<JudgeModel>,
+        threshold: f64,
+    },
+}
+
+/// Generates one output per case, preserving case order.
+pub async fn record_outputs(
+    cases: &[EvalCase],
+    tested_model: Arc<TestedModel>,
+    config: Arc<ModelConfig>,
+) -> Result<Vec<Snapshot>> {
+    let mut futures: FuturesUnordered<_> = cases
+        .iter()
+        .enumerate()
+        .map(|(index, case)| {
+            let tested_model = Arc::clone(&tested_model);
+            let config = Arc::clone(&config);
+            let input = case.input.clone();
+
+            async move {
+                let output = tested_model.respond(&input, &config).await?.output_text();
+                Ok::<_, anyhow::Error>((index, Snapshot { input, output }))
+            }
+        })
+        .collect();
+
+    let mut outputs = Vec::with_capacity(cases.len());
+    while let Some(output) = futures.next().await {
+        outputs.push(output?);
+    }
+    outputs.sort_by_key(|(index, _)| *index);
+
+    Ok(outputs.into_iter().map(|(_, snapshot)| snapshot).collect())
+}
+
+pub async fn compare_snapshots(
+    blessed: &SnapshotFile,
+    outputs: &[Snapshot],
+    matcher: &SnapshotMatcher,
+) -> Result<Vec<SnapshotComparison>> {
+    let blessed_by_input: HashMap<&str, &str> = blessed
+        .snapshots
+        .iter()
+        .map(|snapshot| (snapshot.input.as_str(), snapshot.output.as_str()))
+        .collect();
+
+    let mut comparisons = Vec::with_capacity(outputs.len());
+    for output in outputs {
+        let status = match blessed_by_input.get(output.input.as_str()) {
+            None => SnapshotStatus::New,
+            Some(blessed_output) => match matcher {
+                SnapshotMatcher::Exact => {
+                    if blessed_output.trim() == output.output.trim() {
+                        SnapshotStatus::Matched
+                    } else {
+                        SnapshotStatus::Changed {
+                            blessed_output: blessed_output.to_string(),
+                            reasoning: None,
+                        }
+                    }
+                }
+                SnapshotMatcher::Judge { judge, threshold } => {
+                    let case = EvalCase {
+                        input: output.input.clone(),
+                        expected_output: Some(ExpectedOutput::String(blessed_output.to_string())),
+                        metadata: HashMap::new(),
+                        scorer: None,
+                        setup: None,
+                        teardown: None,
+                    };
+                    let score = judge.evaluate(&case, &output.output).await?;
+                    if score.score >= *threshold {
+                        SnapshotStatus::Matched
+                    } else {
+                        SnapshotStatus::Changed {
+                            blessed_output: blessed_output.to_string(),
+                            reasoning: Some(score.reasoning),
+                        }
+                    }
+                }
+            },
+        };
+
+        comparisons.push(SnapshotComparison {
+            input: output.input.clone(),
+            actual_output: output.output.clone(),
+            status,
+        });
+    }
+
+    Ok(comparisons)
+}
@@ -0,0 +1,138 @@
+use std::sync::Arc;
+
+use anyhow::{Result, anyhow};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    conversation_model::{ConversationConifg, ConversationModel, GenerationResult, ToolDefinition},
+    evaluation::EvaluationReport,
+    model_config::ModelConfig,
+};
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct PromptSuggestions {
+    pub suggestions: Vec<PromptSuggestion>,
+    pub revised_prompt: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct PromptSuggestion {
+    pub issue: String,
+    pub change: String,
+    pub rationale: String,
+}
+
+pub struct PromptSuggester {
+    model: Arc<dyn ConversationModel>,
+    config: ModelConfig,
+}
+
+impl PromptSuggester {
+    pub fn new(model: Arc<dyn ConversationModel>, config: ModelConfig) -> Self {
+        Self { model, config }
+    }
+
+    /// Asks the model for concrete revisions of `system_prompt` based on up to
+    /// `sample_size` failing cases of `report`.
+    pub async fn suggest(
+        &self,
+        system_prompt: &str,
+        report: &EvaluationReport,
+        sample_size: usize,
+    ) -> Result<PromptSuggestions> {
+        let failures: Vec<String> = report
+            .results
+            .iter()
+            .filter(|result| !result.passed)
+            .take(sample_size)
+            .map(|result| {
+                let iteration = result
+                    .iterations
+                    .iter()
+                    .find(|iteration| !iteration.passed)
+                    .or(result.iterations.first());
+                let expected = result
+                    .case
+                    .expected_output
+                    .as_ref()
+                    .map(|expected| serde_json::to_string(expected).unwrap_or_default())
+                    .unwrap_or_else(|| "N/A".to_string());
+
+                format!(
+                    "Input: {}\nExpected: {}\nActual: {}\nJudge score: {:.2}\nJudge reasoning: {}",
+                    result.case.input,
+                    expected,
+                    iteration.map_or("", |i| i.actual_output.as_str()),
+                    result.judge_score,
+                    iteration.map_or("", |i| i.judge_reasoning.as_str()),
+                )
+            })
+            .collect();
+
+        if failures.is_empty() {
+            return Err(anyhow!("The report has no failing cases to learn from"));
+        }
+
+        let suggest_tool = ToolDefinition {
+            name: "suggest_prompt_revisions".to_string(),
+            description: "Propose concrete revisions to a system prompt".to_string(),
+            schema: serde_json::json!({
+                "type": "object",
+                "properties": {
+                    "suggestions": {
+                        "type": "array",
+                        "items": {
+                            "type": "object",
+                            "properties": {
+                                "issue": {
+                                    "type": "string",
+                                    "description": "The failure pattern this addresses"
+                                },
+                                "change": {
+                                    "type": "string",
+                                    "description": "The concrete edit to make to the prompt"
+                                },
+                                "rationale": {
+                                    "type": "string",
+                                    "description": "Why the edit should fix the failures"
+                                }
+                            },
+                            "required": ["issue", "change", "rationale"]
+                        }
+                    },
+                    "revised_prompt": {
+                        "type": "string",
+                        "description": "The full system prompt with all suggestions applied"
+                    }
+                },
+                "required": ["suggestions", "revised_prompt"]
+            }),
+        };
+
+        let mut config = self.config.clone();
+        config.system = Some("You are a prompt engineer improving the system prompt of an AI assistant from evaluation failures. You must use the suggest_prompt_revisions tool. Prefer small, targeted edits over rewrites.".into());
+        config.tools = Some(vec![suggest_tool]);
+
+        let internal_config = ConversationConifg::new(config)
+            .with_forced_tool("suggest_prompt_revisions".to_string());
+
+        let prompt = format!(
+            "Current system prompt:\n<system_prompt>\n{}\n</system_prompt>\n\nFailing cases:\n\n{}",
+            system_prompt,
+            failures.join("\n\n---\n\n")
+        );
+
+        let results = self
+            .model
+            .generate(&prompt, &internal_config)
+            .await?
+            .results;
+        for result in results {
+            if let GenerationResult::ToolUse { arguments, .. } = result {
+                return Ok(serde_json::from_value(arguments)?);
+            }
+        }
+
+        Err(anyhow!("Expected tool use response from suggestion model"))
+    }
+}
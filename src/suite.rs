@@ -0,0 +1,143 @@
+use std::collections::{BTreeMap, HashMap};
+
+use anyhow::{Result, anyhow};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    cases_source::{base_dir, is_remote},
+    evaluation::{EvalCase, ExpectedOutput, ExpectedOutputObject},
+    preprocess::Preprocessor,
+    session::SessionState,
+};
+
+/// A cases file: either a bare array of cases, or an object that also
+/// configures how the suite runs.
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct Suite {
+    pub cases: Vec<EvalCase>,
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub categories: HashMap<String, CategoryConfig>,
+    /// State injected into the context of every case.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub session: Option<SessionState>,
+    /// Values for `{{name}}` tags in case inputs and expected outputs.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub variables: BTreeMap<String, String>,
+    /// Expands `{{ ... }}` tags even without `variables`, e.g. for includes only.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub preprocess: bool,
+    /// Metadata keys to break the pass rate down by in reports, besides `category`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub breakdown_by: Vec<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct CategoryConfig {
+    /// Run the category's cases one at a time, e.g. when their MCP tools
+    /// mutate shared state.
+    #[serde(default)]
+    pub serial: bool,
+}
+
+impl From<Vec<EvalCase>> for Suite {
+    fn from(cases: Vec<EvalCase>) -> Self {
+        Suite {
+            cases,
+            ..Default::default()
+        }
+    }
+}
+
+impl Suite {
+    /// Parses a cases file, checking its shape first so that errors point at
+    /// the offending field rather than at the choice of shape.
+    pub fn from_json(content: &str) -> Result<Self> {
+        if content.trim_start().starts_with('[') {
+            Ok(serde_json::from_str::<Vec<EvalCase>>(content)?.into())
+        } else {
+            Ok(serde_json::from_str(content)?)
+        }
+    }
+
+    pub fn from_value(value: serde_json::Value) -> Result<Self> {
+        if value.is_array() {
+            Ok(serde_json::from_value::<Vec<EvalCase>>(value)?.into())
+        } else {
+            Ok(serde_json::from_value(value)?)
+        }
+    }
+
+    /// Serializes the suite in the shape it was read: a bare array when
+    /// nothing but cases is configured.
+    pub fn to_json(&self) -> Result<String> {
+        if self.categories.is_empty()
+            && self.session.is_none()
+            && self.variables.is_empty()
+            && !self.preprocess
+            && self.breakdown_by.is_empty()
+        {
+            Ok(serde_json::to_string_pretty(&self.cases)?)
+        } else {
+            Ok(serde_json::to_string_pretty(self)?)
+        }
+    }
+
+    /// Whether any case runs code on the host: a `setup` or `teardown`
+    /// command, or a WASM `scorer`.
+    pub fn runs_code(&self) -> bool {
+        self.cases
+            .iter()
+            .any(|case| case.setup.is_some() || case.teardown.is_some() || case.scorer.is_some())
+    }
+
+    /// Refuses cases that run code on the host when they come from a remote
+    /// cases file, unless `allow_case_commands` is set.
+    pub fn check_case_commands(&self, source: &str, allow_case_commands: bool) -> Result<()> {
+        if is_remote(source) && !allow_case_commands && self.runs_code() {
+            return Err(anyhow!(
+                "Remote cases file '{}' has \"setup\", \"teardown\" or \"scorer\" cases; pass --allow-case-commands to run them",
+                source
+            ));
+        }
+        Ok(())
+    }
+
+    /// Expands variables, file inclusions and transforms in every case's
+    /// input and expected output; included paths are relative to the cases
+    /// file at `source`, and remote cases files may not include local files.
+    /// Suites that define no `variables` and don't set `preprocess` are left
+    /// as they are.
+    pub async fn preprocess(&mut self, source: &str) -> Result<()> {
+        let preprocessor = Preprocessor::new(self.variables.clone(), base_dir(source));
+        let preprocessor = if is_remote(source) {
+            preprocessor.without_includes()
+        } else {
+            preprocessor
+        };
+        self.preprocess_with(preprocessor).await
+    }
+
+    /// Like [`Suite::preprocess`], with a preprocessor that may restrict
+    /// included files.
+    pub async fn preprocess_with(&mut self, mut preprocessor: Preprocessor) -> Result<()> {
+        if self.variables.is_empty() && !self.preprocess {
+            return Ok(());
+        }
+
+        for case in &mut self.cases {
+            case.input = preprocessor.render(&case.input).await?;
+            match &mut case.expected_output {
+                Some(ExpectedOutput::String(content))
+                | Some(ExpectedOutput::Object(ExpectedOutputObject::ContentComparison {
+                    description: content,
+                }))
+                | Some(ExpectedOutput::Object(ExpectedOutputObject::BehaviorDescription {
+                    description: content,
+                })) => *content = preprocessor.render(content).await?,
+                None => {}
+            }
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,151 @@
+use std::{fmt, str::FromStr};
+
+use anyhow::{Result, anyhow};
+use serde::{Deserialize, Serialize};
+
+use crate::{evaluation::EvaluationReport, model_config::ModelConfig};
+
+/// A model setting varied across the runs of a sweep.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum SweepParameter {
+    Temperature,
+    TopP,
+    MaxTokens,
+    Model,
+}
+
+impl fmt::Display for SweepParameter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SweepParameter::Temperature => write!(f, "temperature"),
+            SweepParameter::TopP => write!(f, "top_p"),
+            SweepParameter::MaxTokens => write!(f, "max_tokens"),
+            SweepParameter::Model => write!(f, "model"),
+        }
+    }
+}
+
+impl FromStr for SweepParameter {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "temperature" => Ok(SweepParameter::Temperature),
+            "top_p" | "top-p" => Ok(SweepParameter::TopP),
+            "max_tokens" | "max-tokens" => Ok(SweepParameter::MaxTokens),
+            "model" => Ok(SweepParameter::Model),
+            _ => Err(anyhow!("Unsupported sweep parameter: {}", s)),
+        }
+    }
+}
+
+impl SweepParameter {
+    /// Parses `value` for this parameter, as a number where applicable.
+    pub fn parse_value(&self, value: &str) -> Result<serde_json::Value> {
+        let value = value.trim();
+        match self {
+            SweepParameter::Temperature | SweepParameter::TopP => {
+                let number: f64 = value
+                    .parse()
+                    .map_err(|_| anyhow!("Invalid {} value: {}", self, value))?;
+                Ok(serde_json::json!(number))
+            }
+            SweepParameter::MaxTokens => {
+                let number: u32 = value
+                    .parse()
+                    .map_err(|_| anyhow!("Invalid {} value: {}", self, value))?;
+                Ok(serde_json::json!(number))
+            }
+            SweepParameter::Model => Ok(serde_json::json!(value)),
+        }
+    }
+
+    /// Returns a copy of `config` with this parameter set to `value`.
+    pub fn apply(&self, config: &ModelConfig, value: &serde_json::Value) -> Result<ModelConfig> {
+        let mut config = config.clone();
+        let invalid = || anyhow!("Invalid {} value: {}", self, value);
+        match self {
+            SweepParameter::Temperature => {
+                config.temperature = Some(value.as_f64().ok_or_else(invalid)?)
+            }
+            SweepParameter::TopP => config.top_p = Some(value.as_f64().ok_or_else(invalid)?),
+            SweepParameter::MaxTokens => {
+                config.max_tokens = value
+                    .as_u64()
+                    .and_then(|max_tokens| u32::try_from(max_tokens).ok())
+                    .ok_or_else(invalid)?
+            }
+            SweepParameter::Model => config.model = value.as_str().ok_or_else(invalid)?.to_string(),
+        }
+        Ok(config)
+    }
+}
+
+/// Expands an inclusive `start:end:step` numeric range.
+pub fn range_values(range: &str) -> Result<Vec<String>> {
+    let parts: Vec<f64> = range
+        .split(':')
+        .map(|part| part.trim().parse::<f64>())
+        .collect::<Result<_, _>>()
+        .map_err(|_| anyhow!("Invalid range '{}', expected start:end:step", range))?;
+
+    let [start, end, step] = parts[..] else {
+        return Err(anyhow!(
+            "Invalid range '{}', expected start:end:step",
+            range
+        ));
+    };
+    if step <= 0.0 || start > end {
+        return Err(anyhow!(
+            "Invalid range '{}', expected start:end:step",
+            range
+        ));
+    }
+
+    let steps = ((end - start) / step + 1e-9).floor() as usize;
+    Ok((0..=steps)
+        .map(|i| (((start + step * i as f64) * 1e6).round() / 1e6).to_string())
+        .collect())
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct SweepPoint {
+    pub value: serde_json::Value,
+    pub pass_rate_percent: f64,
+    pub average_score: f64,
+    pub passed_count: usize,
+    pub total_cases: usize,
+    pub mean_latency_seconds: f64,
+}
+
+impl SweepPoint {
+    pub fn from_report(value: serde_json::Value, report: &EvaluationReport) -> Self {
+        Self {
+            value,
+            pass_rate_percent: report.summary.pass_rate_percent,
+            average_score: report.summary.average_score,
+            passed_count: report.summary.passed_count,
+            total_cases: report.metadata.total_cases,
+            mean_latency_seconds: report.mean_latency_seconds(),
+        }
+    }
+}
+
+/// Scores of a suite at each value of one parameter, in sweep order.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Sweep {
+    pub parameter: SweepParameter,
+    pub points: Vec<SweepPoint>,
+}
+
+impl Sweep {
+    /// The point with the highest pass rate, ties broken by average score.
+    pub fn best(&self) -> Option<&SweepPoint> {
+        self.points.iter().max_by(|a, b| {
+            a.pass_rate_percent
+                .total_cmp(&b.pass_rate_percent)
+                .then(a.average_score.total_cmp(&b.average_score))
+        })
+    }
+}
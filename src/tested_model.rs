@@ -3,7 +3,7 @@ use std::sync::Arc;
 use anyhow::Result;
 
 use crate::{
-    conversation_model::{ConversationConifg, ConversationModel},
+    conversation_model::{ConversationConifg, ConversationModel, GenerationResponse},
     mcp_manager::McpManager,
     model_config::ModelConfig,
 };
@@ -28,7 +28,7 @@ impl TestedModel {
         }
     }
 
-    pub async fn respond(&self, input: &str, config: &ModelConfig) -> Result<String> {
+    pub async fn respond(&self, input: &str, config: &ModelConfig) -> Result<GenerationResponse> {
         let mut enhanced_config = config.clone();
 
         if let Some(mcp_manager) = &self.mcp_manager {
@@ -39,13 +39,6 @@ impl TestedModel {
         }
 
         let internal_config = ConversationConifg::new(enhanced_config);
-        let results = self.model.generate(input, &internal_config).await?;
-
-        let mut response = String::new();
-        for result in results {
-            response.push_str(&format!("{result}\n"));
-        }
-
-        Ok(response.trim().to_string())
+        self.model.generate(input, &internal_config).await
     }
 }
@@ -0,0 +1,162 @@
+use std::collections::HashMap;
+
+use anyhow::{Result, anyhow};
+use serde::{Deserialize, Serialize};
+
+use crate::evaluation::{EvalResult, EvaluationReport};
+
+const LABEL_METADATA_KEY: &str = "human_label";
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct HumanLabel {
+    pub input: String,
+    pub acceptable: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ThresholdMetrics {
+    pub threshold: f64,
+    pub true_positives: usize,
+    pub false_positives: usize,
+    pub false_negatives: usize,
+    pub true_negatives: usize,
+    pub precision: f64,
+    pub recall: f64,
+    pub f1: f64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ThresholdSweep {
+    pub labeled_cases: usize,
+    pub recommended_threshold: f64,
+    pub metrics: Vec<ThresholdMetrics>,
+}
+
+/// Sweeps the pass threshold over `[min, max]` and measures how well the pass
+/// decision agrees with human labels, recommending the threshold with the best F1.
+///
+/// Labels come from `labels` when given, otherwise from each case's
+/// `human_label` metadata ("pass" or "fail").
+pub fn sweep_thresholds(
+    report: &EvaluationReport,
+    labels: Option<&[HumanLabel]>,
+    min: f64,
+    max: f64,
+    step: f64,
+) -> Result<ThresholdSweep> {
+    if step <= 0.0 || min > max {
+        return Err(anyhow!(
+            "Invalid sweep range {}..={} step {}",
+            min,
+            max,
+            step
+        ));
+    }
+
+    let labels_by_input: Option<HashMap<&str, bool>> = labels.map(|labels| {
+        labels
+            .iter()
+            .map(|label| (label.input.as_str(), label.acceptable))
+            .collect()
+    });
+
+    let labeled: Vec<(&EvalResult, bool)> = report
+        .results
+        .iter()
+        .filter_map(|result| {
+            let label = match &labels_by_input {
+                Some(labels) => labels.get(result.case.input.as_str()).copied(),
+                None => result
+                    .case
+                    .metadata
+                    .get(LABEL_METADATA_KEY)
+                    .and_then(|label| match label.to_lowercase().as_str() {
+                        "pass" | "true" => Some(true),
+                        "fail" | "false" => Some(false),
+                        _ => None,
+                    }),
+            };
+            label.map(|label| (result, label))
+        })
+        .collect();
+
+    if labeled.is_empty() {
+        return Err(anyhow!(
+            "No labeled cases found, provide --labels or '{}' metadata",
+            LABEL_METADATA_KEY
+        ));
+    }
+
+    // The epsilon keeps a step that divides the range from being lost to
+    // rounding, without stepping past `max` when it does not.
+    let steps = ((max - min) / step + 1e-9).floor() as usize;
+    let metrics: Vec<ThresholdMetrics> = (0..=steps)
+        .map(|i| {
+            let threshold = (((min + step * i as f64) * 1e6).round() / 1e6).min(max);
+            evaluate_threshold(&labeled, threshold)
+        })
+        .collect();
+
+    let recommended_threshold = metrics
+        .iter()
+        .fold(None::<&ThresholdMetrics>, |best, candidate| match best {
+            Some(best) if best.f1 > candidate.f1 => Some(best),
+            _ => Some(candidate),
+        })
+        .map(|best| best.threshold)
+        .unwrap_or(min);
+
+    Ok(ThresholdSweep {
+        labeled_cases: labeled.len(),
+        recommended_threshold,
+        metrics,
+    })
+}
+
+fn evaluate_threshold(labeled: &[(&EvalResult, bool)], threshold: f64) -> ThresholdMetrics {
+    let mut true_positives = 0;
+    let mut false_positives = 0;
+    let mut false_negatives = 0;
+    let mut true_negatives = 0;
+
+    for (result, acceptable) in labeled {
+        let passed = result
+            .iterations
+            .iter()
+            .any(|iteration| iteration.judge_score >= threshold);
+
+        match (passed, acceptable) {
+            (true, true) => true_positives += 1,
+            (true, false) => false_positives += 1,
+            (false, true) => false_negatives += 1,
+            (false, false) => true_negatives += 1,
+        }
+    }
+
+    let precision = ratio(true_positives, true_positives + false_positives);
+    let recall = ratio(true_positives, true_positives + false_negatives);
+    let f1 = if precision + recall > 0.0 {
+        2.0 * precision * recall / (precision + recall)
+    } else {
+        0.0
+    };
+
+    ThresholdMetrics {
+        threshold,
+        true_positives,
+        false_positives,
+        false_negatives,
+        true_negatives,
+        precision,
+        recall,
+        f1,
+    }
+}
+
+fn ratio(numerator: usize, denominator: usize) -> f64 {
+    if denominator == 0 {
+        0.0
+    } else {
+        numerator as f64 / denominator as f64
+    }
+}
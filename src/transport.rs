@@ -0,0 +1,168 @@
+pub mod cassette;
+
+use std::{path::PathBuf, sync::Arc};
+
+use anyhow::{Result, anyhow};
+use object_store::ClientOptions;
+
+use crate::model_config::Provider;
+
+#[derive(Debug, Clone)]
+pub struct HttpResponse {
+    pub status: u16,
+    pub retry_after: Option<String>,
+    pub body: serde_json::Value,
+}
+
+/// Sends JSON requests to provider APIs.
+#[async_trait::async_trait]
+pub trait HttpTransport: Send + Sync {
+    async fn post_json(
+        &self,
+        url: &str,
+        headers: &[(&str, String)],
+        body: &serde_json::Value,
+    ) -> Result<HttpResponse>;
+}
+
+pub struct ReqwestTransport {
+    client: reqwest::Client,
+}
+
+impl ReqwestTransport {
+    pub fn new() -> Self {
+        Self::with_client(reqwest::Client::new())
+    }
+
+    pub fn with_client(client: reqwest::Client) -> Self {
+        Self { client }
+    }
+}
+
+impl Default for ReqwestTransport {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait::async_trait]
+impl HttpTransport for ReqwestTransport {
+    async fn post_json(
+        &self,
+        url: &str,
+        headers: &[(&str, String)],
+        body: &serde_json::Value,
+    ) -> Result<HttpResponse> {
+        let mut request = self.client.post(url).json(body);
+        for (name, value) in headers {
+            request = request.header(*name, value);
+        }
+
+        let response = request.send().await?;
+        let status = response.status().as_u16();
+        let retry_after = response
+            .headers()
+            .get("retry-after")
+            .and_then(|value| value.to_str().ok())
+            .map(str::to_string);
+
+        let text = response.text().await?;
+        let body = if text.is_empty() {
+            serde_json::Value::Null
+        } else {
+            serde_json::from_str(&text)
+                .map_err(|e| anyhow!("Invalid JSON response (HTTP {}): {}: {}", status, e, text))?
+        };
+
+        Ok(HttpResponse {
+            status,
+            retry_after,
+            body,
+        })
+    }
+}
+
+/// How provider HTTP traffic is recorded to or replayed from cassettes.
+#[derive(Debug, Clone)]
+pub enum CassetteMode {
+    Record(String),
+    Replay(String),
+}
+
+/// Network settings for every outbound request.
+#[derive(Debug, Clone, Default)]
+pub struct HttpConfig {
+    /// Proxy URL for all outbound requests; `HTTP(S)_PROXY` are honoured otherwise.
+    pub proxy: Option<String>,
+    /// PEM bundle of extra root certificates, e.g. an internal CA.
+    pub ca_bundle: Option<PathBuf>,
+    pub anthropic_base_url: Option<String>,
+    pub openai_base_url: Option<String>,
+    pub cassettes: Option<CassetteMode>,
+}
+
+impl HttpConfig {
+    pub fn base_url(&self, provider: Provider) -> Option<&str> {
+        match provider {
+            Provider::Anthropic => self.anthropic_base_url.as_deref(),
+            Provider::OpenAI | Provider::OpenAIResponses => self.openai_base_url.as_deref(),
+        }
+    }
+
+    pub fn client(&self) -> Result<reqwest::Client> {
+        let mut builder = reqwest::Client::builder();
+
+        if let Some(proxy) = &self.proxy {
+            builder = builder.proxy(reqwest::Proxy::all(proxy)?);
+        }
+
+        if let Some(pem) = self.ca_bundle_pem()? {
+            for certificate in reqwest::Certificate::from_pem_bundle(&pem)? {
+                builder = builder.add_root_certificate(certificate);
+            }
+        }
+
+        Ok(builder.build()?)
+    }
+
+    /// The proxy and certificates for object storage uploads, which use their
+    /// own client; `None` when neither is configured.
+    pub fn client_options(&self) -> Result<Option<ClientOptions>> {
+        if self.proxy.is_none() && self.ca_bundle.is_none() {
+            return Ok(None);
+        }
+
+        let mut options = ClientOptions::new();
+        if let Some(proxy) = &self.proxy {
+            options = options.with_proxy_url(proxy);
+        }
+        if let Some(pem) = self.ca_bundle_pem()? {
+            for certificate in object_store::Certificate::from_pem_bundle(&pem)? {
+                options = options.with_root_certificate(certificate);
+            }
+        }
+
+        Ok(Some(options))
+    }
+
+    fn ca_bundle_pem(&self) -> Result<Option<Vec<u8>>> {
+        self.ca_bundle
+            .as_ref()
+            .map(|ca_bundle| {
+                std::fs::read(ca_bundle)
+                    .map_err(|e| anyhow!("Failed to read CA bundle {}: {}", ca_bundle.display(), e))
+            })
+            .transpose()
+    }
+}
+
+pub fn create_transport(config: &HttpConfig) -> Result<Arc<dyn HttpTransport>> {
+    match &config.cassettes {
+        None => Ok(Arc::new(ReqwestTransport::with_client(config.client()?))),
+        Some(CassetteMode::Record(dir)) => Ok(Arc::new(cassette::RecordingTransport::new(
+            Arc::new(ReqwestTransport::with_client(config.client()?)),
+            dir,
+        ))),
+        Some(CassetteMode::Replay(dir)) => Ok(Arc::new(cassette::ReplayTransport::new(dir))),
+    }
+}
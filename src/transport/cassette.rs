@@ -0,0 +1,123 @@
+use std::{path::PathBuf, sync::Arc};
+
+use anyhow::{Result, anyhow};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use super::{HttpResponse, HttpTransport};
+
+/// A recorded request/response pair. Request headers are never stored, so
+/// cassettes do not leak API keys.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Interaction {
+    pub request: RecordedRequest,
+    pub response: RecordedResponse,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct RecordedRequest {
+    pub url: String,
+    pub body: serde_json::Value,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct RecordedResponse {
+    pub status: u16,
+    pub body: serde_json::Value,
+}
+
+/// Cassette file name for a request, derived from its URL and body.
+pub fn cassette_name(url: &str, body: &serde_json::Value) -> String {
+    let host = url
+        .split("://")
+        .nth(1)
+        .and_then(|rest| rest.split('/').next())
+        .unwrap_or("unknown");
+    let digest = Sha256::digest(format!("{url}\n{body}").as_bytes());
+    format!("{}-{:x}.json", host, digest)
+}
+
+pub struct RecordingTransport {
+    inner: Arc<dyn HttpTransport>,
+    dir: PathBuf,
+}
+
+impl RecordingTransport {
+    pub fn new(inner: Arc<dyn HttpTransport>, dir: impl Into<PathBuf>) -> Self {
+        Self {
+            inner,
+            dir: dir.into(),
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl HttpTransport for RecordingTransport {
+    async fn post_json(
+        &self,
+        url: &str,
+        headers: &[(&str, String)],
+        body: &serde_json::Value,
+    ) -> Result<HttpResponse> {
+        let response = self.inner.post_json(url, headers, body).await?;
+
+        // Rate-limited attempts are retried by the providers; only the final answer is kept.
+        if response.status != 429 {
+            let interaction = Interaction {
+                request: RecordedRequest {
+                    url: url.to_string(),
+                    body: body.clone(),
+                },
+                response: RecordedResponse {
+                    status: response.status,
+                    body: response.body.clone(),
+                },
+            };
+            tokio::fs::create_dir_all(&self.dir).await?;
+            tokio::fs::write(
+                self.dir.join(cassette_name(url, body)),
+                serde_json::to_string_pretty(&interaction)?,
+            )
+            .await?;
+        }
+
+        Ok(response)
+    }
+}
+
+pub struct ReplayTransport {
+    dir: PathBuf,
+}
+
+impl ReplayTransport {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+}
+
+#[async_trait::async_trait]
+impl HttpTransport for ReplayTransport {
+    async fn post_json(
+        &self,
+        url: &str,
+        _headers: &[(&str, String)],
+        body: &serde_json::Value,
+    ) -> Result<HttpResponse> {
+        let path = self.dir.join(cassette_name(url, body));
+        let content = tokio::fs::read_to_string(&path).await.map_err(|e| {
+            anyhow!(
+                "No cassette recorded for {} ({}): {}",
+                url,
+                path.display(),
+                e
+            )
+        })?;
+        let interaction: Interaction = serde_json::from_str(&content)?;
+
+        Ok(HttpResponse {
+            status: interaction.response.status,
+            retry_after: None,
+            body: interaction.response.body,
+        })
+    }
+}
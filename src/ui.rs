@@ -1,8 +1,30 @@
 use indicatif::{ProgressBar, ProgressStyle};
 use owo_colors::OwoColorize;
-use std::time::Duration;
+use std::{
+    collections::{BTreeMap, HashMap},
+    time::Duration,
+};
 
-use crate::{EvalResult, ModelConfig};
+use evals::{
+    compare::{ReportComparison, WordChange, word_diff},
+    daemon::DaemonEvent,
+    difficulty::DifficultyBucket,
+    early_stopping::EarlyStoppingReport,
+    evaluation::CategoryStats,
+    evaluation::EvalResult,
+    evaluation::EvaluationReport,
+    evaluation::ExpectedOutputObject,
+    evaluation::PromptVersion,
+    failure_analysis::FailureMode,
+    judge::failure_category_counts,
+    leaderboard::Leaderboard,
+    model_config::ModelConfig,
+    rescore::RescoredTrend,
+    snapshot::{SnapshotComparison, SnapshotStatus},
+    suggest::PromptSuggestions,
+    sweep::Sweep,
+    threshold_tuning::ThresholdSweep,
+};
 
 pub struct TerminalUI {
     progress_bar: Option<ProgressBar>,
@@ -188,6 +210,459 @@ impl TerminalUI {
             }
             println!();
         }
+
+        self.print_difficulty_breakdown(results);
+
+        let generation_failures = results
+            .iter()
+            .flat_map(|result| result.iterations.iter())
+            .filter(|iteration| iteration.generation_failure.is_some())
+            .count();
+        if generation_failures > 0 {
+            println!(
+                "  {} {}",
+                "!".yellow(),
+                format!(
+                    "{} generation failures not sent to the judge",
+                    generation_failures
+                )
+                .yellow()
+            );
+        }
+
+        let truncated = results
+            .iter()
+            .flat_map(|result| result.iterations.iter())
+            .filter(|iteration| iteration.truncated)
+            .count();
+        if truncated > 0 {
+            println!(
+                "  {} {}",
+                "!".yellow(),
+                format!(
+                    "{} outputs cut off by the token limit (see --max-tokens-cap)",
+                    truncated
+                )
+                .yellow()
+            );
+        }
+
+        let failure_categories = failure_category_counts(results);
+        if !failure_categories.is_empty() {
+            let counts: Vec<String> = failure_categories
+                .iter()
+                .map(|(category, count)| format!("{} {}", category.to_string().dimmed(), count))
+                .collect();
+            println!("  {} {}", "Failures:".dimmed(), counts.join(" · "));
+        }
+    }
+
+    /// Prints each failed case with its judge reasoning and, for content
+    /// comparisons, a word diff of the expected content against the output.
+    pub fn print_failure_details(&self, results: &[EvalResult]) {
+        for result in results.iter().filter(|result| !result.passed) {
+            println!(
+                "  {} {} {}",
+                "✗".red(),
+                result.case.input.bold(),
+                format!("[{}]", result.case.id()).dimmed()
+            );
+            for note in &result.notes {
+                println!("    {} {}", "note:".cyan(), note.text);
+            }
+
+            let Some(iteration) = result.iterations.first() else {
+                continue;
+            };
+            println!(
+                "    {} {}",
+                format!("{:.0}%", iteration.judge_score * 100.0).red(),
+                iteration.judge_reasoning.dimmed()
+            );
+
+            if let Some(ExpectedOutputObject::ContentComparison { description }) =
+                &result.case.expected_output
+            {
+                let diff: String = word_diff(description, &iteration.actual_output)
+                    .into_iter()
+                    .map(|(change, text)| match change {
+                        WordChange::Equal => text,
+                        WordChange::Removed => text.red().strikethrough().to_string(),
+                        WordChange::Added => text.green().underline().to_string(),
+                    })
+                    .collect();
+                println!("    {}", diff.replace('\n', "\n    "));
+            }
+        }
+    }
+
+    fn print_difficulty_breakdown(&self, results: &[EvalResult]) {
+        let mut buckets = Vec::new();
+        for bucket in [
+            DifficultyBucket::Easy,
+            DifficultyBucket::Medium,
+            DifficultyBucket::Hard,
+        ] {
+            let in_bucket = results.iter().filter(|result| {
+                result
+                    .difficulty
+                    .as_ref()
+                    .is_some_and(|difficulty| difficulty.bucket() == bucket)
+            });
+            let (total, passed) = in_bucket.fold((0, 0), |(total, passed), result| {
+                (total + 1, passed + usize::from(result.passed))
+            });
+            if total > 0 {
+                buckets.push(format!(
+                    "{} {}/{}",
+                    bucket.to_string().dimmed(),
+                    passed,
+                    total
+                ));
+            }
+        }
+
+        if !buckets.is_empty() {
+            println!("  {}", buckets.join(" · "));
+        }
+    }
+
+    /// Prints one line per breakdown key, warning about keys no case has.
+    pub fn print_breakdowns(
+        &self,
+        breakdowns: &BTreeMap<String, HashMap<String, CategoryStats>>,
+        keys: &[String],
+    ) {
+        for key in keys {
+            let Some(breakdown) = breakdowns.get(key) else {
+                println!(
+                    "  {} {}",
+                    "!".yellow(),
+                    format!("No case has '{}' metadata to break down by", key).yellow()
+                );
+                continue;
+            };
+
+            let mut groups: Vec<_> = breakdown.iter().collect();
+            groups.sort_by(|a, b| a.0.cmp(b.0));
+            let groups: Vec<String> = groups
+                .into_iter()
+                .map(|(value, stats)| {
+                    format!(
+                        "{} {}/{} ({:.0}%)",
+                        value.dimmed(),
+                        stats.passed,
+                        stats.total,
+                        stats.pass_rate_percent
+                    )
+                })
+                .collect();
+            println!("  {} {}", format!("{}:", key).bold(), groups.join(" · "));
+        }
+    }
+
+    pub fn print_early_stopping(&self, early_stopping: &EarlyStoppingReport) {
+        let status = if early_stopping.stopped_early {
+            format!(
+                "stopped after {}/{} cases",
+                early_stopping.evaluated_cases, early_stopping.total_cases
+            )
+        } else {
+            format!("all {} cases evaluated", early_stopping.total_cases)
+        };
+        println!(
+            "  {} · pass rate {:.0}–{:.0}% at {:.0}% confidence",
+            status.dimmed(),
+            early_stopping.pass_rate_lower_percent,
+            early_stopping.pass_rate_upper_percent,
+            early_stopping.confidence * 100.0
+        );
+    }
+
+    pub fn print_failure_modes(&self, failure_modes: &[FailureMode]) {
+        if failure_modes.is_empty() {
+            return;
+        }
+
+        println!("  {}", "top failure modes".dimmed());
+        for failure_mode in failure_modes {
+            println!(
+                "    {} {} {}",
+                "✗".red(),
+                failure_mode.theme.bold(),
+                format!("({} cases)", failure_mode.case_count).dimmed()
+            );
+        }
+    }
+
+    pub fn print_suggestions(&self, suggestions: &PromptSuggestions) {
+        for (i, suggestion) in suggestions.suggestions.iter().enumerate() {
+            println!(
+                "  {} {}",
+                format!("#{}", i + 1).dimmed(),
+                suggestion.issue.bold()
+            );
+            println!("    {} {}", "change".dimmed(), suggestion.change);
+            println!("    {} {}", "why".dimmed(), suggestion.rationale);
+        }
+    }
+
+    pub fn print_threshold_sweep(&self, sweep: &ThresholdSweep, current_threshold: f64) {
+        println!("  {} labeled cases", sweep.labeled_cases.to_string().bold());
+        println!(
+            "  {}",
+            format!(
+                "{:>9} {:>9} {:>9} {:>9}",
+                "threshold", "precision", "recall", "f1"
+            )
+            .dimmed()
+        );
+
+        for metrics in &sweep.metrics {
+            let line = format!(
+                "{:>9.2} {:>9.2} {:>9.2} {:>9.2}",
+                metrics.threshold, metrics.precision, metrics.recall, metrics.f1
+            );
+            if metrics.threshold == sweep.recommended_threshold {
+                println!("  {} {}", line.green(), "← recommended".green());
+            } else if (metrics.threshold - current_threshold).abs() < 1e-9 {
+                println!("  {} {}", line, "← current".dimmed());
+            } else {
+                println!("  {}", line);
+            }
+        }
+    }
+
+    /// Prints changed and new snapshots followed by a summary, returning the
+    /// number of changed snapshots.
+    pub fn print_snapshot_comparisons(&self, comparisons: &[SnapshotComparison]) -> usize {
+        let mut matched = 0;
+        let mut changed = 0;
+        let mut new = 0;
+
+        for (i, comparison) in comparisons.iter().enumerate() {
+            let case_number = format!("#{}", i + 1);
+            match &comparison.status {
+                SnapshotStatus::Matched => matched += 1,
+                SnapshotStatus::Changed {
+                    blessed_output,
+                    reasoning,
+                } => {
+                    changed += 1;
+                    println!(
+                        "  {} {} {}",
+                        "✗".red(),
+                        case_number.dimmed(),
+                        comparison.input
+                    );
+                    println!("    {} {}", "-".red(), blessed_output.red());
+                    println!("    {} {}", "+".green(), comparison.actual_output.green());
+                    if let Some(reasoning) = reasoning {
+                        println!("    {}", reasoning.dimmed());
+                    }
+                }
+                SnapshotStatus::New => {
+                    new += 1;
+                    println!(
+                        "  {} {} {}",
+                        "+".yellow(),
+                        case_number.dimmed(),
+                        comparison.input
+                    );
+                }
+            }
+        }
+
+        println!(
+            "  {} matched · {} changed · {} new",
+            matched.to_string().green(),
+            changed.to_string().red(),
+            new.to_string().yellow()
+        );
+
+        changed
+    }
+
+    pub fn print_sweep(&self, sweep: &Sweep) {
+        println!(
+            "  {}",
+            format!(
+                "{:>24} {:>9} {:>9} {:>9}",
+                sweep.parameter, "pass %", "avg", "latency"
+            )
+            .dimmed()
+        );
+
+        let best = sweep.best().map(|point| &point.value);
+        for point in &sweep.points {
+            let value = match &point.value {
+                serde_json::Value::String(value) => value.clone(),
+                value => value.to_string(),
+            };
+            let line = format!(
+                "{:>24} {:>9.1} {:>9.2} {:>8.2}s",
+                value, point.pass_rate_percent, point.average_score, point.mean_latency_seconds
+            );
+            if Some(&point.value) == best {
+                println!("  {} {}", line.green(), "← best".green());
+            } else {
+                println!("  {}", line);
+            }
+        }
+    }
+
+    pub fn print_comparison(
+        &self,
+        baseline: &EvaluationReport,
+        candidate: &EvaluationReport,
+        comparison: &ReportComparison,
+    ) {
+        for (label, report) in [("baseline", baseline), ("candidate", candidate)] {
+            println!(
+                "  {} {} {} · {:.0}% pass · avg {:.2} · system {} · judge {}",
+                format!("{:<9}", label).dimmed(),
+                report.configuration.provider.dimmed(),
+                report.configuration.model.bold(),
+                report.summary.pass_rate_percent,
+                report.summary.average_score,
+                prompt_label(report.metadata.system_prompt.as_ref()),
+                prompt_label(report.metadata.judge_prompt.as_ref())
+            );
+            for note in &report.metadata.notes {
+                println!("  {:<9} {} {}", "", "note:".cyan(), note.text);
+            }
+        }
+
+        let delta = format!(
+            "{:+.1} pass rate · {:+.2} avg",
+            comparison.pass_rate_delta, comparison.average_score_delta
+        );
+        if comparison.pass_rate_delta >= 0.0 {
+            println!("  {}", delta.green());
+        } else {
+            println!("  {}", delta.red());
+        }
+
+        for input in &comparison.regressions {
+            println!("    {} {}", "✗".red(), input);
+        }
+        for input in &comparison.improvements {
+            println!("    {} {}", "✓".green(), input);
+        }
+
+        for (name, diff) in [
+            ("system prompt", &comparison.system_prompt_diff),
+            ("judge prompt", &comparison.judge_prompt_diff),
+        ] {
+            match diff {
+                Some(diff) => {
+                    println!("  {} {}", name.bold(), "changed".yellow());
+                    for line in diff.lines() {
+                        if line.starts_with("+++") || line.starts_with("---") {
+                            println!("    {}", line.dimmed());
+                        } else if line.starts_with('+') {
+                            println!("    {}", line.green());
+                        } else if line.starts_with('-') {
+                            println!("    {}", line.red());
+                        } else if line.starts_with("@@") {
+                            println!("    {}", line.cyan());
+                        } else {
+                            println!("    {}", line);
+                        }
+                    }
+                }
+                None => println!("  {} {}", name.bold(), "unchanged".dimmed()),
+            }
+        }
+    }
+
+    pub fn print_daemon_event(&self, event: &DaemonEvent) {
+        match event {
+            DaemonEvent::Scheduled { suite, at } => {
+                println!(
+                    "{} {} {}",
+                    "⏱".dimmed(),
+                    suite.bold(),
+                    format!("next run at {}", at.format("%Y-%m-%d %H:%M:%S UTC")).dimmed()
+                );
+            }
+            DaemonEvent::Completed {
+                suite,
+                report,
+                saved_to,
+                degradations,
+            } => {
+                let icon = if degradations.is_empty() {
+                    "✓".green().to_string()
+                } else {
+                    "✗".red().to_string()
+                };
+                println!(
+                    "{} {} {:.0}% pass · avg {:.2} · {}",
+                    icon,
+                    suite.bold(),
+                    report.summary.pass_rate_percent,
+                    report.summary.average_score,
+                    saved_to.display().dimmed()
+                );
+                for degradation in degradations {
+                    println!("  {} {}", "↓".red(), degradation.to_string().red());
+                }
+            }
+            DaemonEvent::Failed { suite, error } => {
+                println!("{} {} {}", "✗".red(), suite.bold(), error.to_string().red());
+            }
+        }
+    }
+
+    pub fn print_leaderboard(&self, leaderboard: &Leaderboard) {
+        println!("🏆 {}", leaderboard.suite.bold());
+        for entry in &leaderboard.entries {
+            let cost = entry
+                .cost_usd
+                .map_or_else(|| "—".to_string(), |cost| format!("${:.4}", cost));
+            println!(
+                "  {} {} {} · {:.0}% pass · avg {:.2} · {} · {:.2}s",
+                format!("#{}", entry.rank).dimmed(),
+                entry.provider.dimmed(),
+                entry.model.bold(),
+                entry.pass_rate_percent,
+                entry.average_score,
+                cost,
+                entry.mean_latency_seconds
+            );
+        }
+    }
+
+    pub fn print_rescored_trend(&self, trend: &RescoredTrend) {
+        println!(
+            "📈 {} {}",
+            trend.suite.bold(),
+            format!("judged by {}", trend.judge_model).dimmed()
+        );
+        for point in &trend.points {
+            let delta = point.pass_rate_percent - point.original_pass_rate_percent;
+            let delta = format!("{:+.0}", delta);
+            println!(
+                "  {} {} {} · {:.0}% → {:.0}% pass ({}) · avg {:.2} → {:.2}",
+                point
+                    .generated_at
+                    .format("%Y-%m-%d %H:%M")
+                    .to_string()
+                    .dimmed(),
+                point.provider.dimmed(),
+                point.model.bold(),
+                point.original_pass_rate_percent,
+                point.pass_rate_percent,
+                if point.pass_rate_percent >= point.original_pass_rate_percent {
+                    delta.green().to_string()
+                } else {
+                    delta.red().to_string()
+                },
+                point.original_average_score,
+                point.average_score
+            );
+        }
     }
 
     fn print_pass_at_k_details(&self, results: &[EvalResult]) {
@@ -217,3 +692,13 @@ impl TerminalUI {
         }
     }
 }
+
+fn prompt_label(prompt: Option<&PromptVersion>) -> String {
+    match prompt {
+        Some(prompt) => match &prompt.version {
+            Some(version) => format!("{} ({})", version, prompt.short_hash()),
+            None => prompt.short_hash().to_string(),
+        },
+        None => "—".to_string(),
+    }
+}
@@ -0,0 +1,290 @@
+use std::sync::Arc;
+
+use evals::{
+    conversation_model::{
+        ConversationConifg, ConversationModel, GenerationResult, StopReason, ToolDefinition,
+        anthropic::AnthropicModel, openai::OpenAIModel, openai_responses::OpenAIResponsesModel,
+    },
+    model_config::{ModelConfig, Provider},
+    transport::{
+        HttpResponse, HttpTransport,
+        cassette::{RecordingTransport, ReplayTransport},
+    },
+};
+
+const CASSETTES: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/cassettes");
+
+fn config(provider: Provider, model: &str) -> ModelConfig {
+    ModelConfig::builder()
+        .provider(provider)
+        .model(model)
+        .max_tokens(100)
+        .build()
+}
+
+fn anthropic() -> AnthropicModel {
+    AnthropicModel::with_transport(
+        String::new(),
+        Arc::new(ReplayTransport::new(format!("{CASSETTES}/anthropic"))),
+    )
+}
+
+fn openai() -> OpenAIModel {
+    OpenAIModel::with_transport(
+        String::new(),
+        Arc::new(ReplayTransport::new(format!("{CASSETTES}/openai"))),
+    )
+}
+
+fn openai_responses() -> OpenAIResponsesModel {
+    OpenAIResponsesModel::with_transport(
+        String::new(),
+        Arc::new(ReplayTransport::new(format!(
+            "{CASSETTES}/openai-responses"
+        ))),
+    )
+}
+
+fn score_tool() -> ToolDefinition {
+    ToolDefinition {
+        name: "score".to_string(),
+        description: "Score the answer".to_string(),
+        schema: serde_json::json!({
+            "type": "object",
+            "properties": {"score": {"type": "number"}},
+            "required": ["score"]
+        }),
+    }
+}
+
+fn with_score_tool(provider: Provider, model: &str) -> ConversationConifg {
+    let config = ModelConfig::builder()
+        .provider(provider)
+        .model(model)
+        .max_tokens(100)
+        .tools(vec![score_tool()])
+        .build();
+    ConversationConifg::new(config).with_forced_tool("score".to_string())
+}
+
+#[tokio::test]
+async fn anthropic_text_response() {
+    let config = ConversationConifg::new(config(Provider::Anthropic, "claude-sonnet-4-0"));
+    let response = anthropic()
+        .generate("What is 2 + 2?", &config)
+        .await
+        .unwrap();
+
+    assert!(matches!(&response.results[..], [GenerationResult::Text(text)] if text == "4"));
+    assert_eq!(response.metadata.stop_reason, Some(StopReason::EndTurn));
+    assert_eq!(
+        response.metadata.model.as_deref(),
+        Some("claude-sonnet-4-0")
+    );
+    let usage = response.metadata.usage.unwrap();
+    assert_eq!((usage.input_tokens, usage.output_tokens), (14, 5));
+}
+
+#[tokio::test]
+async fn anthropic_tool_use_response() {
+    let config = with_score_tool(Provider::Anthropic, "claude-sonnet-4-0");
+    let results = anthropic()
+        .generate("Score: 4", &config)
+        .await
+        .unwrap()
+        .results;
+
+    match &results[..] {
+        [GenerationResult::ToolUse { name, arguments }] => {
+            assert_eq!(name, "score");
+            assert_eq!(arguments["score"], 0.9);
+        }
+        other => panic!("unexpected results: {other:?}"),
+    }
+}
+
+#[tokio::test]
+async fn anthropic_error_response_without_content() {
+    let config = ConversationConifg::new(config(Provider::Anthropic, "claude-unknown"));
+    let error = anthropic()
+        .generate("What is 2 + 2?", &config)
+        .await
+        .unwrap_err();
+
+    assert!(error.to_string().contains("No valid content"));
+}
+
+#[tokio::test]
+async fn openai_text_response() {
+    let config = ConversationConifg::new(config(Provider::OpenAI, "gpt-4o-mini"));
+    let response = openai().generate("What is 2 + 2?", &config).await.unwrap();
+
+    assert!(matches!(&response.results[..], [GenerationResult::Text(text)] if text == "4"));
+    assert_eq!(response.metadata.stop_reason, Some(StopReason::EndTurn));
+    assert_eq!(response.metadata.model.as_deref(), Some("gpt-4o-mini"));
+    let usage = response.metadata.usage.unwrap();
+    assert_eq!((usage.input_tokens, usage.output_tokens), (14, 1));
+}
+
+#[tokio::test]
+async fn openai_tool_call_response() {
+    let config = with_score_tool(Provider::OpenAI, "gpt-4o-mini");
+    let results = openai()
+        .generate("Score: 4", &config)
+        .await
+        .unwrap()
+        .results;
+
+    match &results[..] {
+        [GenerationResult::ToolUse { name, arguments }] => {
+            assert_eq!(name, "score");
+            assert_eq!(arguments["score"], 0.9);
+        }
+        other => panic!("unexpected results: {other:?}"),
+    }
+}
+
+#[tokio::test]
+async fn openai_logprobs_response() {
+    let config = ConversationConifg::new(
+        ModelConfig::builder()
+            .provider(Provider::OpenAI)
+            .model("gpt-4o-mini")
+            .max_tokens(100)
+            .logprobs(2)
+            .build(),
+    );
+    let response = openai()
+        .generate("What is the capital of France?", &config)
+        .await
+        .unwrap();
+
+    let logprobs = response.logprobs.as_ref().unwrap();
+    assert_eq!(logprobs.len(), 1);
+    assert_eq!(logprobs[0].token, "Paris");
+    assert_eq!(logprobs[0].top_logprobs.len(), 2);
+    assert_eq!(response.output_text(), "Paris");
+}
+
+#[tokio::test]
+async fn openai_responses_text_response() {
+    let config = ConversationConifg::new(config(Provider::OpenAIResponses, "gpt-4o-mini"));
+    let response = openai_responses()
+        .generate("What is 2 + 2?", &config)
+        .await
+        .unwrap();
+
+    assert!(matches!(&response.results[..], [GenerationResult::Text(text)] if text == "4"));
+    assert_eq!(response.metadata.stop_reason, Some(StopReason::EndTurn));
+    assert_eq!(
+        response.metadata.model.as_deref(),
+        Some("gpt-4o-mini-2024-07-18")
+    );
+    let usage = response.metadata.usage.unwrap();
+    assert_eq!((usage.input_tokens, usage.output_tokens), (13, 2));
+}
+
+#[tokio::test]
+async fn openai_responses_function_call_response() {
+    let config = with_score_tool(Provider::OpenAIResponses, "gpt-4o-mini");
+    let response = openai_responses()
+        .generate("Score: 4", &config)
+        .await
+        .unwrap();
+
+    match &response.results[..] {
+        [GenerationResult::ToolUse { name, arguments }] => {
+            assert_eq!(name, "score");
+            assert_eq!(arguments["score"], 0.9);
+        }
+        other => panic!("unexpected results: {other:?}"),
+    }
+    assert_eq!(response.metadata.stop_reason, Some(StopReason::ToolUse));
+}
+
+#[tokio::test]
+async fn openai_responses_builtin_tool_response() {
+    let config = ConversationConifg::new(
+        ModelConfig::builder()
+            .provider(Provider::OpenAIResponses)
+            .model("gpt-4o-mini")
+            .max_tokens(100)
+            .builtin_tools(vec![serde_json::json!({"type": "web_search"})])
+            .build(),
+    );
+    let response = openai_responses()
+        .generate("Where were the 2024 Summer Olympics held?", &config)
+        .await
+        .unwrap();
+
+    assert_eq!(
+        response.output_text(),
+        "The 2024 Summer Olympics were held in Paris."
+    );
+    assert_eq!(response.metadata.stop_reason, Some(StopReason::EndTurn));
+}
+
+#[tokio::test]
+async fn replay_without_cassette_fails() {
+    let config = ConversationConifg::new(config(Provider::OpenAI, "gpt-4o-mini"));
+    let error = openai()
+        .generate("Never recorded", &config)
+        .await
+        .unwrap_err();
+
+    assert!(error.to_string().contains("No cassette recorded"));
+}
+
+struct CannedTransport(serde_json::Value);
+
+#[async_trait::async_trait]
+impl HttpTransport for CannedTransport {
+    async fn post_json(
+        &self,
+        _url: &str,
+        _headers: &[(&str, String)],
+        _body: &serde_json::Value,
+    ) -> anyhow::Result<HttpResponse> {
+        Ok(HttpResponse {
+            status: 200,
+            retry_after: None,
+            body: self.0.clone(),
+        })
+    }
+}
+
+#[tokio::test]
+async fn recorded_cassettes_replay_without_api_key() {
+    let dir = std::env::temp_dir().join(format!("evals-cassettes-{}", uuid::Uuid::new_v4()));
+    let response = serde_json::json!({
+        "content": [{"type": "text", "text": "Paris"}]
+    });
+    let config = ConversationConifg::new(config(Provider::Anthropic, "claude-sonnet-4-0"));
+
+    let recording = AnthropicModel::with_transport(
+        "sk-ant-secret".to_string(),
+        Arc::new(RecordingTransport::new(
+            Arc::new(CannedTransport(response)),
+            &dir,
+        )),
+    );
+    recording
+        .generate("What is the capital of France?", &config)
+        .await
+        .unwrap();
+
+    let mut entries = std::fs::read_dir(&dir).unwrap();
+    let cassette = std::fs::read_to_string(entries.next().unwrap().unwrap().path()).unwrap();
+    assert!(!cassette.contains("sk-ant-secret"));
+
+    let replaying =
+        AnthropicModel::with_transport(String::new(), Arc::new(ReplayTransport::new(&dir)));
+    let results = replaying
+        .generate("What is the capital of France?", &config)
+        .await
+        .unwrap()
+        .results;
+    assert!(matches!(&results[..], [GenerationResult::Text(text)] if text == "Paris"));
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
@@ -0,0 +1,75 @@
+use chrono::{TimeZone, Utc};
+use evals::{
+    history::HistoryStore,
+    judge::JudgePrompt,
+    model_config::{ModelConfig, Provider},
+    runner::generate_report,
+    schema::{EvalCaseReport, EvalResult},
+};
+
+fn result() -> EvalResult {
+    EvalResult {
+        case: EvalCaseReport {
+            input: "What is the capital of France?".to_string(),
+            expected_output: None,
+            metadata: Default::default(),
+            scorer: None,
+        },
+        judge_score: 1.0,
+        passed: true,
+        iterations: Vec::new(),
+        pass_at_k: None,
+        difficulty: None,
+        mean_logprob: None,
+        notes: Vec::new(),
+    }
+}
+
+fn config(provider: Provider, model: &str) -> ModelConfig {
+    ModelConfig::builder()
+        .provider(provider)
+        .model(model)
+        .max_tokens(100)
+        .build()
+}
+
+#[tokio::test]
+async fn latest_matches_the_provider_and_model() {
+    let root = std::env::temp_dir().join(format!("evals-history-{}", std::process::id()));
+    let store = HistoryStore::new(&root);
+    let sonnet = config(Provider::Anthropic, "claude-sonnet-4-20250514");
+    let gpt = config(Provider::OpenAI, "gpt-4o");
+
+    for (minute, config) in [(0, &sonnet), (1, &gpt), (2, &sonnet), (3, &gpt)] {
+        let mut report =
+            generate_report(&[result()], config, &JudgePrompt::default(), 0.8, 1.0).unwrap();
+        report.metadata.suite = Some("support".to_string());
+        report.metadata.generated_at = Utc.with_ymd_and_hms(2026, 1, 1, 0, minute, 0).unwrap();
+        store.save(&report).await.unwrap();
+    }
+
+    let latest = store.latest("support", &sonnet).await.unwrap().unwrap();
+    assert_eq!(latest.configuration.model, sonnet.model);
+    assert_eq!(latest.metadata.generated_at.format("%M").to_string(), "02");
+    let other = config(Provider::OpenAI, "gpt-4.1");
+    assert!(store.latest("support", &other).await.unwrap().is_none());
+
+    std::fs::remove_dir_all(&root).unwrap();
+}
+
+#[tokio::test]
+async fn dot_suite_names_cannot_leave_the_root() {
+    let root = std::env::temp_dir().join(format!("evals-history-dots-{}", std::process::id()));
+    let store = HistoryStore::new(root.join("history"));
+    let config = config(Provider::Anthropic, "claude-sonnet-4-20250514");
+
+    for suite in ["..", ".", ""] {
+        let mut report =
+            generate_report(&[result()], &config, &JudgePrompt::default(), 0.8, 1.0).unwrap();
+        report.metadata.suite = Some(suite.to_string());
+
+        assert!(store.save(&report).await.is_err(), "{suite:?}");
+        assert!(store.load_suite(suite).await.is_err(), "{suite:?}");
+    }
+    assert!(!root.exists());
+}
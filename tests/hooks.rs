@@ -0,0 +1,27 @@
+use std::time::{Duration, Instant};
+
+use evals::hooks::run_shell_command;
+
+#[tokio::test]
+async fn timeout_covers_a_payload_the_command_never_reads() {
+    let payload = vec![b'x'; 1024 * 1024];
+    let start = Instant::now();
+
+    let error = run_shell_command("sleep 30", &payload, Some(Duration::from_millis(500)))
+        .await
+        .unwrap_err();
+
+    assert!(error.to_string().contains("timed out"), "{error}");
+    assert!(start.elapsed() < Duration::from_secs(10));
+}
+
+#[tokio::test]
+async fn command_receives_the_payload() {
+    run_shell_command(
+        r#"test "$(cat)" = '{"event":"pre_run"}'"#,
+        br#"{"event":"pre_run"}"#,
+        Some(Duration::from_secs(10)),
+    )
+    .await
+    .unwrap();
+}
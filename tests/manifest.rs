@@ -0,0 +1,84 @@
+use evals::{
+    evaluation::EvalCase,
+    judge::JudgePrompt,
+    manifest::{RecordedFile, RunManifest},
+    model_config::{ModelConfig, Provider, SystemPrompt},
+};
+
+const CASES: &str =
+    r#"[{"input": "What is the capital of France?", "expected_output": "Paris", "metadata": {}}]"#;
+
+fn manifest(system: &str, hooks: &str) -> RunManifest {
+    let cases: Vec<_> = serde_json::from_str(CASES).unwrap();
+    let config = ModelConfig::builder()
+        .provider(Provider::Anthropic)
+        .model("claude-sonnet-4-20250514")
+        .system(SystemPrompt::Text(system.to_string()))
+        .build();
+
+    let mut manifest = RunManifest::new(
+        "cases.json",
+        CASES,
+        &cases,
+        config,
+        JudgePrompt::default(),
+        0.8,
+    );
+    manifest.hooks = Some(RecordedFile::new("hooks.json", hooks));
+    manifest
+}
+
+#[test]
+fn unchanged_inputs_do_not_drift() {
+    let recorded = manifest("Be brief.", r#"{"pre_run": []}"#);
+
+    assert!(
+        recorded
+            .drift(&manifest("Be brief.", r#"{"pre_run": []}"#))
+            .is_empty()
+    );
+}
+
+#[test]
+fn edited_system_prompt_and_hooks_drift() {
+    let recorded = manifest("Be brief.", r#"{"pre_run": []}"#);
+
+    let drift = recorded.drift(&manifest("Be thorough.", r#"{"post_run": []}"#));
+
+    assert_eq!(drift.len(), 2, "{drift:?}");
+    assert!(drift[0].starts_with("system prompt"), "{drift:?}");
+    assert!(drift[1].starts_with("hooks"), "{drift:?}");
+}
+
+#[tokio::test]
+async fn edited_case_scripts_and_cassettes_drift() {
+    let dir = std::env::temp_dir().join(format!("evals-manifest-{}", std::process::id()));
+    let script = dir.join("reset.sh");
+    let cassettes = dir.join("cassettes");
+    std::fs::create_dir_all(&cassettes).unwrap();
+    let cases: Vec<EvalCase> = serde_json::from_value(serde_json::json!([{
+        "input": "hi",
+        "expected_output": "hello",
+        "metadata": {},
+        "setup": {"command": format!("sh {}", script.display())}
+    }]))
+    .unwrap();
+    let hashed = async |script_content: &str, cassette_content: &str| {
+        std::fs::write(&script, script_content).unwrap();
+        std::fs::write(cassettes.join("request.json"), cassette_content).unwrap();
+        let mut manifest = manifest("Be brief.", "{}");
+        manifest.cassettes = Some(cassettes.to_string_lossy().to_string());
+        manifest.hash_files(&cases).await.unwrap();
+        manifest
+    };
+
+    let recorded = hashed("true", "{}").await;
+    assert_eq!(recorded.case_scripts.len(), 1);
+    assert!(recorded.drift(&hashed("true", "{}").await).is_empty());
+    let drift = recorded.drift(&hashed("false", r#"{"edited": true}"#).await);
+
+    assert_eq!(drift.len(), 2, "{drift:?}");
+    assert!(drift[0].starts_with("case scripts"), "{drift:?}");
+    assert!(drift[1].starts_with("cassettes"), "{drift:?}");
+    std::fs::remove_dir_all(&dir).unwrap();
+}
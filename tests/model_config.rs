@@ -0,0 +1,20 @@
+use evals::model_config::{ModelConfig, Provider};
+
+fn config(max_tokens: u32, max_tokens_cap: Option<u32>) -> ModelConfig {
+    ModelConfig {
+        max_tokens_cap,
+        ..ModelConfig::builder()
+            .provider(Provider::Anthropic)
+            .model("claude-sonnet-4-20250514")
+            .max_tokens(max_tokens)
+            .build()
+    }
+}
+
+#[test]
+fn token_limits_must_leave_room_to_retry() {
+    assert!(config(100, Some(400)).validate().is_ok());
+    assert!(config(0, None).validate().is_err());
+    assert!(config(0, Some(400)).validate().is_err());
+    assert!(config(400, Some(400)).validate().is_err());
+}
@@ -0,0 +1,33 @@
+use evals::{
+    conversation_model::TokenUsage,
+    pricing::{ModelPrice, PriceTable},
+};
+
+#[test]
+fn longest_prefix_prices_the_model() {
+    let prices = PriceTable::default();
+
+    assert_eq!(
+        prices.price("gpt-4o-mini-2024-07-18"),
+        Some(ModelPrice {
+            input: 0.15,
+            output: 0.6
+        })
+    );
+    assert_eq!(prices.price("unknown-model"), None);
+}
+
+#[test]
+fn cost_is_per_million_tokens() {
+    let price = ModelPrice {
+        input: 3.0,
+        output: 15.0,
+    };
+
+    let cost = price.cost(&TokenUsage {
+        input_tokens: 1_000_000,
+        output_tokens: 100_000,
+    });
+
+    assert!((cost - 4.5).abs() < 1e-9);
+}
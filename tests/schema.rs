@@ -0,0 +1,150 @@
+use std::collections::HashMap;
+
+use evals::{
+    judge::JudgePrompt,
+    model_config::{ModelConfig, Provider},
+    runner::generate_report,
+    schema::{
+        EvalCaseReport, EvalError, EvalResult, EvaluationReport, FailureCategory,
+        GenerationMetadata, IterationResult, Note, PassAtKStats, SCHEMA_VERSION, StopReason,
+        TokenLogprob, TokenUsage,
+    },
+};
+
+fn iteration(score: f64, passed: bool) -> IterationResult {
+    IterationResult {
+        actual_output: "Paris".to_string(),
+        judge_score: score,
+        judge_reasoning: "Matches the expected answer".to_string(),
+        judge_output: Some(serde_json::json!({"score": score, "confidence": "high"})),
+        passed,
+        failure_category: (!passed).then_some(FailureCategory::Incompleteness),
+        generation: GenerationMetadata {
+            stop_reason: Some(StopReason::EndTurn),
+            model: Some("claude-sonnet-4-20250514".to_string()),
+            usage: Some(TokenUsage {
+                input_tokens: 12,
+                output_tokens: 3,
+            }),
+            latency_seconds: 0.5,
+        },
+        truncated: false,
+        max_tokens: None,
+        generation_failure: None,
+        logprobs: Some(vec![TokenLogprob {
+            token: "Paris".to_string(),
+            logprob: -0.25,
+            top_logprobs: Vec::new(),
+        }]),
+    }
+}
+
+fn report() -> EvaluationReport {
+    let results = vec![
+        EvalResult {
+            case: EvalCaseReport {
+                input: "What is the capital of France?".to_string(),
+                expected_output: None,
+                metadata: HashMap::from([("category".to_string(), "geography".to_string())]),
+                scorer: None,
+            },
+            judge_score: 0.75,
+            passed: true,
+            iterations: vec![iteration(1.0, true), iteration(0.5, false)],
+            pass_at_k: Some(PassAtKStats {
+                total_iterations: 2,
+                passed_iterations: 1,
+                pass_rate: 0.5,
+            }),
+            difficulty: None,
+            mean_logprob: Some(-0.25),
+            notes: vec![Note::new("flaky on long prompts", Some("ana".to_string()))],
+        },
+        EvalResult {
+            case: EvalCaseReport {
+                input: "What is 2 + 2?".to_string(),
+                expected_output: None,
+                metadata: HashMap::from([("id".to_string(), "arithmetic".to_string())]),
+                scorer: None,
+            },
+            judge_score: 0.0,
+            passed: false,
+            iterations: vec![iteration(0.0, false)],
+            pass_at_k: None,
+            difficulty: None,
+            mean_logprob: None,
+            notes: Vec::new(),
+        },
+    ];
+    let config = ModelConfig::builder()
+        .provider(Provider::Anthropic)
+        .model("claude-sonnet-4-20250514")
+        .max_tokens(100)
+        .build();
+
+    let mut report = generate_report(&results, &config, &JudgePrompt::default(), 0.8, 1.5).unwrap();
+    report.metadata.judge_model = Some("claude-sonnet-4-20250514".to_string());
+    report.annotate(None, Note::new("baseline", None)).unwrap();
+    report
+}
+
+#[test]
+fn report_round_trips() {
+    let report = report();
+    assert_eq!(report.schema_version, SCHEMA_VERSION);
+
+    let json = serde_json::to_value(&report).unwrap();
+    let parsed = EvaluationReport::from_json(&json.to_string()).unwrap();
+
+    assert_eq!(serde_json::to_value(&parsed).unwrap(), json);
+}
+
+#[test]
+fn unversioned_report_parses_as_version_zero() {
+    let mut json = serde_json::to_value(report()).unwrap();
+    json.as_object_mut().unwrap().remove("schema_version");
+
+    let parsed = EvaluationReport::from_json(&json.to_string()).unwrap();
+
+    assert_eq!(parsed.schema_version, 0);
+    assert_eq!(parsed.results.len(), 2);
+}
+
+#[test]
+fn newer_schema_version_is_rejected() {
+    let mut json = serde_json::to_value(report()).unwrap();
+    json["schema_version"] = serde_json::json!(SCHEMA_VERSION + 1);
+
+    let error = EvaluationReport::from_json(&json.to_string()).unwrap_err();
+
+    assert_eq!(
+        error,
+        EvalError::UnsupportedSchemaVersion {
+            found: SCHEMA_VERSION + 1,
+            supported: SCHEMA_VERSION,
+        }
+    );
+}
+
+#[test]
+fn invalid_report_is_an_error() {
+    let error = EvaluationReport::from_json(r#"{"schema_version": 1}"#).unwrap_err();
+
+    assert!(matches!(error, EvalError::InvalidReport { .. }));
+}
+
+#[test]
+fn error_round_trips() {
+    let error = EvalError::UnsupportedSchemaVersion {
+        found: 2,
+        supported: 1,
+    };
+
+    let json = serde_json::to_value(&error).unwrap();
+
+    assert_eq!(
+        json,
+        serde_json::json!({"type": "unsupported_schema_version", "found": 2, "supported": 1})
+    );
+    assert_eq!(serde_json::from_value::<EvalError>(json).unwrap(), error);
+}
@@ -0,0 +1,130 @@
+use evals::{preprocess::Preprocessor, suite::Suite};
+
+#[test]
+fn parses_both_shapes() {
+    let case = r#"{"input": "hi", "expected_output": "hello", "metadata": {}}"#;
+
+    let bare = Suite::from_json(&format!("[{case}]")).unwrap();
+    let configured = Suite::from_json(&format!(
+        r#"{{"cases": [{case}], "variables": {{"a": "b"}}}}"#
+    ))
+    .unwrap();
+
+    assert_eq!(bare.cases.len(), 1);
+    assert_eq!(configured.cases.len(), 1);
+    assert_eq!(configured.variables["a"], "b");
+}
+
+#[test]
+fn errors_name_the_offending_field() {
+    let case = r#"{"input": "hi", "expected_output": "hello", "metdata": {}}"#;
+
+    for content in [format!("[{case}]"), format!(r#"{{"cases": [{case}]}}"#)] {
+        let error = Suite::from_json(&content).unwrap_err().to_string();
+
+        assert!(error.contains("missing field `metadata`"), "{error}");
+        assert!(error.contains("line 1"), "{error}");
+    }
+}
+
+#[tokio::test]
+async fn confined_includes_stay_in_the_root() {
+    let root = std::env::temp_dir().join(format!("evals-confined-{}", std::process::id()));
+    std::fs::create_dir_all(root.join("suite")).unwrap();
+    std::fs::write(root.join("context.md"), "context").unwrap();
+    let outside = root.with_extension("md");
+    std::fs::write(&outside, "secret").unwrap();
+    let variables = Default::default();
+
+    let mut preprocessor = Preprocessor::new(variables, root.join("suite")).confine_to(&root);
+    assert_eq!(
+        preprocessor
+            .render(r#"{{include "../context.md"}}"#)
+            .await
+            .unwrap(),
+        "context"
+    );
+    let error = preprocessor
+        .render(&format!(
+            r#"{{{{include "../../{}"}}}}"#,
+            outside.file_name().unwrap().to_string_lossy()
+        ))
+        .await
+        .unwrap_err();
+    assert!(error.to_string().contains("outside"), "{error}");
+
+    let mut preprocessor = Preprocessor::new(Default::default(), &root).without_includes();
+    assert!(
+        preprocessor
+            .render(r#"{{include "context.md"}}"#)
+            .await
+            .is_err()
+    );
+
+    std::fs::remove_dir_all(&root).unwrap();
+    std::fs::remove_file(&outside).unwrap();
+}
+
+#[tokio::test]
+async fn suites_without_variables_are_not_templated() {
+    let content =
+        r#"[{"input": "Render {{x}} with Jinja", "expected_output": "{{x}}", "metadata": {}}]"#;
+    let mut suite = Suite::from_json(content).unwrap();
+
+    suite.preprocess(".").await.unwrap();
+
+    assert_eq!(suite.cases[0].input, "Render {{x}} with Jinja");
+    assert_eq!(
+        suite.to_json().unwrap(),
+        Suite::from_json(content).unwrap().to_json().unwrap()
+    );
+}
+
+#[tokio::test]
+async fn escaped_braces_are_literal() {
+    let mut suite = Suite::from_json(
+        r#"{"variables": {"x": "Paris"}, "cases": [{"input": "{{x}} in \\{{x}}", "expected_output": "Paris", "metadata": {}}]}"#,
+    )
+    .unwrap();
+
+    suite.preprocess(".").await.unwrap();
+
+    assert_eq!(suite.cases[0].input, "Paris in {{x}}");
+}
+
+#[tokio::test]
+async fn remote_suites_cannot_include_local_files() {
+    let mut suite = Suite::from_json(
+        r#"{"preprocess": true, "cases": [{"input": "{{include \"Cargo.toml\"}}", "expected_output": "x", "metadata": {}}]}"#,
+    )
+    .unwrap();
+
+    let error = suite
+        .preprocess("https://example.com/suite.json")
+        .await
+        .unwrap_err();
+
+    assert!(error.to_string().contains("Refusing"), "{error}");
+}
+
+#[test]
+fn remote_case_commands_need_opting_in() {
+    let suite = Suite::from_json(
+        r#"[{"input": "hi", "expected_output": "hello", "metadata": {}, "setup": {"command": "true"}}]"#,
+    )
+    .unwrap();
+
+    assert!(suite.check_case_commands("cases.json", false).is_ok());
+    assert!(
+        suite
+            .check_case_commands("https://example.com/cases.json", true)
+            .is_ok()
+    );
+    let error = suite
+        .check_case_commands("https://example.com/cases.json", false)
+        .unwrap_err();
+    assert!(
+        error.to_string().contains("--allow-case-commands"),
+        "{error}"
+    );
+}
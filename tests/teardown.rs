@@ -0,0 +1,64 @@
+use std::{sync::Arc, time::Duration};
+
+use anyhow::{Result, anyhow};
+use evals::{
+    conversation_model::{ConversationConifg, ConversationModel, GenerationResponse},
+    evaluation::EvalCase,
+    model_config::{ModelConfig, Provider},
+    runner::EvalRun,
+};
+
+/// Fails the case whose input is "fail" after a moment and never answers the
+/// others.
+struct StallingModel;
+
+#[async_trait::async_trait]
+impl ConversationModel for StallingModel {
+    async fn generate(
+        &self,
+        prompt: &str,
+        _config: &ConversationConifg,
+    ) -> Result<GenerationResponse> {
+        if prompt == "fail" {
+            tokio::time::sleep(Duration::from_millis(500)).await;
+            return Err(anyhow!("generation failed"));
+        }
+        tokio::time::sleep(Duration::from_secs(60)).await;
+        Err(anyhow!("unreachable"))
+    }
+}
+
+#[tokio::test]
+async fn teardown_runs_for_cases_cancelled_by_a_failing_case() {
+    let marker = std::env::temp_dir().join(format!("evals-teardown-{}", std::process::id()));
+    let _ = std::fs::remove_file(&marker);
+    let cases: Vec<EvalCase> = serde_json::from_value(serde_json::json!([
+        {
+            "input": "slow",
+            "expected_output": null,
+            "metadata": {},
+            "setup": {"command": "true"},
+            "teardown": {"command": format!("touch {}", marker.display())}
+        },
+        {"input": "fail", "expected_output": null, "metadata": {}}
+    ]))
+    .unwrap();
+    let config = ModelConfig::builder()
+        .provider(Provider::Anthropic)
+        .model("model")
+        .build();
+
+    let error = EvalRun::builder(config)
+        .cases(cases)
+        .model(Arc::new(StallingModel))
+        .judge_model(Arc::new(StallingModel))
+        .build()
+        .unwrap()
+        .execute()
+        .await
+        .unwrap_err();
+
+    assert!(error.to_string().contains("generation failed"), "{error}");
+    assert!(marker.exists());
+    std::fs::remove_file(&marker).unwrap();
+}
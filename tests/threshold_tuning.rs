@@ -0,0 +1,51 @@
+use std::collections::HashMap;
+
+use evals::{
+    judge::JudgePrompt,
+    model_config::{ModelConfig, Provider},
+    runner::generate_report,
+    schema::{EvalCaseReport, EvalResult},
+    threshold_tuning::sweep_thresholds,
+};
+
+fn result(score: f64, label: &str) -> EvalResult {
+    EvalResult {
+        case: EvalCaseReport {
+            input: format!("case scored {score}"),
+            expected_output: None,
+            metadata: HashMap::from([("human_label".to_string(), label.to_string())]),
+            scorer: None,
+        },
+        judge_score: score,
+        passed: score >= 0.8,
+        iterations: Vec::new(),
+        pass_at_k: None,
+        difficulty: None,
+        mean_logprob: None,
+        notes: Vec::new(),
+    }
+}
+
+#[test]
+fn sweep_stays_within_the_range_when_the_step_does_not_divide_it() {
+    let config = ModelConfig::builder()
+        .provider(Provider::Anthropic)
+        .model("claude-sonnet-4-20250514")
+        .build();
+    let results = [result(0.9, "pass"), result(0.5, "fail")];
+    let report = generate_report(&results, &config, &JudgePrompt::default(), 0.8, 1.0).unwrap();
+
+    let sweep = sweep_thresholds(&report, None, 0.0, 1.0, 0.4).unwrap();
+    let thresholds: Vec<f64> = sweep
+        .metrics
+        .iter()
+        .map(|metrics| metrics.threshold)
+        .collect();
+
+    assert_eq!(thresholds, [0.0, 0.4, 0.8]);
+    assert!(sweep.recommended_threshold <= 1.0);
+
+    let sweep = sweep_thresholds(&report, None, 0.0, 1.0, 0.1).unwrap();
+    assert_eq!(sweep.metrics.len(), 11);
+    assert_eq!(sweep.metrics.last().unwrap().threshold, 1.0);
+}
@@ -0,0 +1,50 @@
+use evals::{
+    evaluation::EvalCase,
+    scorer::{Scorer, wasm::WasmScorer},
+};
+
+const RESPONSE: &str = r#"{"score": 0.9, "reasoning": "close enough"}"#;
+
+fn scorer(name: &str, score_body: &str) -> WasmScorer {
+    let wat = format!(
+        r#"(module
+            (memory (export "memory") 1)
+            (data (i32.const 0) "{}")
+            (func (export "alloc") (param i32) (result i32) (i32.const 1024))
+            (func (export "score") (param i32 i32) (result i64) {}))"#,
+        RESPONSE.replace('"', "\\\""),
+        score_body
+    );
+    let path = std::env::temp_dir().join(format!("evals-{}-{}.wasm", name, std::process::id()));
+    std::fs::write(&path, wat::parse_str(wat).unwrap()).unwrap();
+
+    WasmScorer::load(path.to_str().unwrap()).unwrap()
+}
+
+fn case() -> EvalCase {
+    serde_json::from_value(serde_json::json!({
+        "input": "What is the capital of France?",
+        "expected_output": "Paris",
+        "metadata": {}
+    }))
+    .unwrap()
+}
+
+#[tokio::test]
+async fn scores_with_module_response() {
+    let scorer = scorer("response", &format!("(i64.const {})", RESPONSE.len()));
+
+    let score = scorer.score(&case(), "Paris, France").await.unwrap();
+
+    assert_eq!(score.score, 0.9);
+    assert_eq!(score.reasoning, "close enough");
+}
+
+#[tokio::test]
+async fn looping_module_runs_out_of_fuel() {
+    let scorer = scorer("loop", "(loop (br 0)) (i64.const 0)");
+
+    let error = scorer.score(&case(), "Paris").await.unwrap_err();
+
+    assert!(error.to_string().contains("fuel"), "{error}");
+}